@@ -0,0 +1,14 @@
+//! Helpers shared by the benchmarks.
+
+// Reuse the generator of the unit tests, not all of which the benchmarks need.
+#[allow(dead_code)]
+#[path = "../../src/test_util.rs"]
+mod test_util;
+
+use test_util::Lcg;
+
+/// Deterministic pseudo-random values in '[0,2^31)'.
+pub fn random_values(count: usize) -> Vec<u64> {
+    let mut rng = Lcg::new(42);
+    (0..count).map(|_| rng.next_u64()).collect()
+}
@@ -0,0 +1,203 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use petgraph::graph::NodeIndex;
+use petgraph::graph::{DiGraph, Graph, UnGraph};
+use rad::graph::{shortest_distance, shortest_distance_addressable};
+use rad::graph::{shortest_distance_radix, shortest_distance_with};
+use rad::heap::fibonacci::FibonacciHeap;
+use rad::heap::indexed::IndexedHeap;
+use rad::heap::pairing::PairingHeap;
+use rad::heap::{Comparator, DaryHeap, Heap, MinComparator};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+mod common;
+
+/// A Dijkstra-like workload where every pop is followed by several pushes of
+/// keys no smaller than the popped one, then the heap is emptied.
+fn relax<const D: usize, C: Comparator<u64> + Default>(keys: &[u64], pushes: usize) -> u64 {
+    let mut heap = DaryHeap::<u64, D, C>::default();
+    let mut acc = 0;
+    heap.push(0);
+    for chunk in keys.chunks(pushes) {
+        let d = heap.pop().unwrap_or(0);
+        acc ^= d;
+        for &k in chunk {
+            heap.push(d + k);
+        }
+    }
+    while let Some(d) = heap.pop() {
+        acc ^= d;
+    }
+    acc
+}
+
+/// The same workload as 'relax' using the standard library heap.
+fn relax_std(keys: &[u64], pushes: usize) -> u64 {
+    let mut heap = BinaryHeap::new();
+    let mut acc = 0;
+    heap.push(Reverse(0));
+    for chunk in keys.chunks(pushes) {
+        let d = heap.pop().map_or(0, |Reverse(d)| d);
+        acc ^= d;
+        for &k in chunk {
+            heap.push(Reverse(d + k));
+        }
+    }
+    while let Some(Reverse(d)) = heap.pop() {
+        acc ^= d;
+    }
+    acc
+}
+
+fn push_heavy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("heap");
+    let pushes = 4;
+    for &n in [1 << 12, 1 << 16, 1 << 20].iter() {
+        let keys = common::random_values(n);
+        group.bench_with_input(BenchmarkId::new("std", n), &keys, |b, keys| {
+            b.iter(|| relax_std(black_box(keys), pushes))
+        });
+        group.bench_with_input(BenchmarkId::new("binary", n), &keys, |b, keys| {
+            b.iter(|| relax::<2, MinComparator>(black_box(keys), pushes))
+        });
+        group.bench_with_input(BenchmarkId::new("4-ary", n), &keys, |b, keys| {
+            b.iter(|| relax::<4, MinComparator>(black_box(keys), pushes))
+        });
+        group.bench_with_input(BenchmarkId::new("8-ary", n), &keys, |b, keys| {
+            b.iter(|| relax::<8, MinComparator>(black_box(keys), pushes))
+        });
+    }
+    group.finish();
+}
+
+/// A complete directed graph on 'n' vertices with pseudo-random integer
+/// weights.
+fn dense_graph(n: u32) -> DiGraph<(), u32> {
+    let weights = common::random_values((n * n) as usize);
+    let mut edges = Vec::new();
+    for u in 0..n {
+        for v in 0..n {
+            if u != v {
+                edges.push((u, v, weights[(u * n + v) as usize] as u32 % 1000 + 1));
+            }
+        }
+    }
+    Graph::from_edges(&edges)
+}
+
+fn dense_dijkstra(c: &mut Criterion) {
+    let mut group = c.benchmark_group("dense_dijkstra");
+    for &n in [100, 400, 1000].iter() {
+        let g = dense_graph(n);
+        let expected = shortest_distance(&g, 0.into());
+        assert_eq!(
+            shortest_distance_addressable::<_, IndexedHeap<(u32, NodeIndex), MinComparator>>(
+                &g,
+                0.into()
+            ),
+            expected
+        );
+        assert_eq!(
+            shortest_distance_addressable::<_, FibonacciHeap<(u32, NodeIndex), MinComparator>>(
+                &g,
+                0.into()
+            ),
+            expected
+        );
+        group.bench_with_input(BenchmarkId::new("binary_heap", n), &g, |b, g| {
+            b.iter(|| shortest_distance(black_box(g), 0.into()))
+        });
+        group.bench_with_input(BenchmarkId::new("heap", n), &g, |b, g| {
+            b.iter(|| {
+                shortest_distance_with::<_, Heap<(u32, NodeIndex), MinComparator>>(
+                    black_box(g),
+                    0.into(),
+                )
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("indexed_heap", n), &g, |b, g| {
+            b.iter(|| {
+                shortest_distance_addressable::<_, IndexedHeap<(u32, NodeIndex), MinComparator>>(
+                    black_box(g),
+                    0.into(),
+                )
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("fibonacci_heap", n), &g, |b, g| {
+            b.iter(|| {
+                shortest_distance_addressable::<_, FibonacciHeap<(u32, NodeIndex), MinComparator>>(
+                    black_box(g),
+                    0.into(),
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+/// A 'side' by 'side' grid with pseudo-random integer weights, resembling a
+/// road network.
+fn grid_graph(side: u32) -> UnGraph<(), u32> {
+    let weights = common::random_values(2 * (side * side) as usize);
+    let mut edges = Vec::new();
+    for r in 0..side {
+        for c in 0..side {
+            let u = r * side + c;
+            let w = weights[2 * u as usize] as u32 % 1000 + 1;
+            if c + 1 < side {
+                edges.push((u, u + 1, w));
+            }
+            let w = weights[2 * u as usize + 1] as u32 % 1000 + 1;
+            if r + 1 < side {
+                edges.push((u, u + side, w));
+            }
+        }
+    }
+    Graph::from_edges(&edges)
+}
+
+fn sparse_dijkstra(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sparse_dijkstra");
+    for &side in [32, 128, 512].iter() {
+        let g = grid_graph(side);
+        let n = side * side;
+        assert_eq!(
+            shortest_distance(&g, 0.into()),
+            shortest_distance_radix(&g, 0.into())
+        );
+        group.bench_with_input(BenchmarkId::new("binary_heap", n), &g, |b, g| {
+            b.iter(|| shortest_distance(black_box(g), 0.into()))
+        });
+        group.bench_with_input(BenchmarkId::new("radix_heap", n), &g, |b, g| {
+            b.iter(|| shortest_distance_radix(black_box(g), 0.into()))
+        });
+        group.bench_with_input(BenchmarkId::new("heap", n), &g, |b, g| {
+            b.iter(|| {
+                shortest_distance_with::<_, Heap<(u32, NodeIndex), MinComparator>>(
+                    black_box(g),
+                    0.into(),
+                )
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("4-ary_heap", n), &g, |b, g| {
+            b.iter(|| {
+                shortest_distance_with::<_, DaryHeap<(u32, NodeIndex), 4, MinComparator>>(
+                    black_box(g),
+                    0.into(),
+                )
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("pairing_heap", n), &g, |b, g| {
+            b.iter(|| {
+                shortest_distance_with::<_, PairingHeap<(u32, NodeIndex), MinComparator>>(
+                    black_box(g),
+                    0.into(),
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, push_heavy, dense_dijkstra, sparse_dijkstra);
+criterion_main!(benches);
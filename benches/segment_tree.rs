@@ -0,0 +1,57 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rad::segment_tree::iterative::IterativeSegmentTree;
+use rad::segment_tree::{SegmentTree, SumSpec};
+
+mod common;
+
+/// Deterministic pseudo-random indices in '[0,n)'.
+fn indices(n: usize, count: usize) -> Vec<usize> {
+    common::random_values(count)
+        .into_iter()
+        .map(|x| (x % n as u64) as usize)
+        .collect()
+}
+
+fn update_and_query(c: &mut Criterion) {
+    let mut group = c.benchmark_group("segment_tree");
+    for &n in [1 << 10, 1 << 16, 1 << 20].iter() {
+        let xs: Vec<i64> = (0..n as i64).collect();
+        let idx = indices(n, 2048);
+
+        let mut bounds_tree = SegmentTree::<SumSpec<i64>>::from_slice(&xs);
+        let mut iterative_tree = IterativeSegmentTree::<SumSpec<i64>>::from_slice(&xs);
+
+        group.bench_with_input(BenchmarkId::new("bounds/update", n), &idx, |b, idx| {
+            b.iter(|| {
+                for &i in idx.iter() {
+                    bounds_tree.update(i, black_box(i as i64));
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("iterative/update", n), &idx, |b, idx| {
+            b.iter(|| {
+                for &i in idx.iter() {
+                    iterative_tree.update(i, black_box(i as i64));
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("bounds/query", n), &idx, |b, idx| {
+            b.iter(|| {
+                idx.windows(2)
+                    .map(|w| bounds_tree.query(w[0].min(w[1])..w[0].max(w[1])))
+                    .sum::<i64>()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("iterative/query", n), &idx, |b, idx| {
+            b.iter(|| {
+                idx.windows(2)
+                    .map(|w| iterative_tree.query(w[0].min(w[1])..w[0].max(w[1])))
+                    .sum::<i64>()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, update_and_query);
+criterion_main!(benches);
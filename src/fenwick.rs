@@ -0,0 +1,471 @@
+//! Fenwick trees (also known as binary indexed trees) for prefix and range
+//! queries over groups.
+
+pub use crate::segment_tree::InvertibleSpec;
+use crate::segment_tree::SumSpec;
+use num::{FromPrimitive, Num};
+use std::iter::FromIterator;
+use std::ops::Range;
+
+/// The lowest set bit of 'i'.
+fn lowbit(i: usize) -> usize {
+    i & i.wrapping_neg()
+}
+
+/// A data-structure which supports updating single elements and querying
+/// prefixes and sub-ranges, using less memory than a segment tree.
+pub struct FenwickTree<Spec: InvertibleSpec> {
+    /// The specification defining the group the elements belong to.
+    spec: Spec,
+    /// One-indexed tree where 'tree[i]' combines the elements in
+    /// '[i - lowbit(i), i)'.
+    tree: Vec<Spec::T>,
+}
+
+impl<Spec: InvertibleSpec + Default> FenwickTree<Spec> {
+    /// Create a Fenwick tree over 'n' values, each set to 'Spec::default()'.
+    ///
+    /// Time complexity: O(n)
+    pub fn new(n: usize) -> Self {
+        FenwickTree::with_spec(Default::default(), n)
+    }
+
+    /// Create a Fenwick tree over the values in 'xs'.
+    ///
+    /// Time complexity: O(n)
+    pub fn from_slice(xs: &[Spec::T]) -> Self {
+        FenwickTree::from_slice_with_spec(Default::default(), xs)
+    }
+}
+
+impl<Spec: InvertibleSpec> FenwickTree<Spec> {
+    /// Create a Fenwick tree over 'n' values using specification 'spec'. Each
+    /// value is set to 'spec.default()'.
+    ///
+    /// Time complexity: O(n)
+    pub fn with_spec(spec: Spec, n: usize) -> Self {
+        FenwickTree {
+            tree: vec![spec.default(); n + 1],
+            spec,
+        }
+    }
+
+    /// Create a Fenwick tree over the values in 'xs' using specification
+    /// 'spec'.
+    ///
+    /// Time complexity: O(n)
+    pub fn from_slice_with_spec(spec: Spec, xs: &[Spec::T]) -> Self {
+        let mut t = FenwickTree::with_spec(spec, xs.len());
+        t.tree[1..].clone_from_slice(xs);
+        for i in 1..t.tree.len() {
+            let j = i + lowbit(i);
+            if j < t.tree.len() {
+                let x = t.tree[i].clone();
+                let y = t.tree[j].clone();
+                t.tree[j] = t.spec.combine(y, x);
+            }
+        }
+        t
+    }
+
+    /// The specification the Fenwick tree was created with.
+    pub fn spec(&self) -> &Spec {
+        &self.spec
+    }
+
+    /// The number of values the Fenwick tree is built over.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.tree.len() - 1
+    }
+
+    /// True if the Fenwick tree is built over zero values, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Combine 'x' into the value at index 'i'.
+    ///
+    /// Panics if 'i' is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn add(&mut self, i: usize, x: Spec::T) {
+        assert!(i < self.len(), "index {} out of bounds", i);
+        let mut i = i + 1;
+        while i < self.tree.len() {
+            let y = self.tree[i].clone();
+            self.tree[i] = self.spec.combine(y, x.clone());
+            i += lowbit(i);
+        }
+    }
+
+    /// Set the value at index 'i' to 'x'.
+    ///
+    /// Panics if 'i' is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn update(&mut self, i: usize, x: Spec::T) {
+        let old = self.get(i);
+        // Take 'old' out of each node before combining 'x' in, rather than
+        // adding the difference, so unsigned sums stay within bounds.
+        let mut i = i + 1;
+        while i < self.tree.len() {
+            let y = self.spec.uncombine(self.tree[i].clone(), old.clone());
+            self.tree[i] = self.spec.combine(y, x.clone());
+            i += lowbit(i);
+        }
+    }
+
+    /// The value at index 'i'.
+    ///
+    /// Panics if 'i' is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn get(&self, i: usize) -> Spec::T {
+        self.query(i..i + 1)
+    }
+
+    /// Combine the values in the prefix '[0,r)'.
+    ///
+    /// Panics if 'r' is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn prefix(&self, r: usize) -> Spec::T {
+        assert!(r <= self.len(), "index {} out of bounds", r);
+        let mut acc = self.spec.default();
+        let mut i = r;
+        while i > 0 {
+            acc = self.spec.combine(acc, self.tree[i].clone());
+            i -= lowbit(i);
+        }
+        acc
+    }
+
+    /// Combine the values in the range '[l,r)'. Returns 'spec.default()' if
+    /// the range is empty.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn query(&self, range: Range<usize>) -> Spec::T {
+        let Range { start, end } = range;
+        assert!(start <= end, "range {}..{} out of bounds", start, end);
+        self.spec.uncombine(self.prefix(end), self.prefix(start))
+    }
+}
+
+impl<Spec: InvertibleSpec> FenwickTree<Spec>
+where
+    Spec::T: PartialOrd,
+{
+    /// Find the smallest 'i' such that the prefix '[0,i]' combines to at least
+    /// 'x', or 'len()' if there is no such 'i'. Prefixes must be
+    /// non-decreasing, for sums this means every value is non-negative.
+    ///
+    /// Time complexity: O(log n)
+    pub fn lower_bound(&self, x: Spec::T) -> usize {
+        let mut acc = self.spec.default();
+        let mut i = 0;
+        let mut step = self.tree.len().next_power_of_two() / 2;
+        while step > 0 {
+            if i + step < self.tree.len() {
+                let next = self.spec.combine(acc.clone(), self.tree[i + step].clone());
+                if next < x {
+                    acc = next;
+                    i += step;
+                }
+            }
+            step /= 2;
+        }
+        i
+    }
+}
+
+impl<Spec: InvertibleSpec + Default> FromIterator<Spec::T> for FenwickTree<Spec> {
+    fn from_iter<I: IntoIterator<Item = Spec::T>>(iter: I) -> Self {
+        let xs: Vec<Spec::T> = iter.into_iter().collect();
+        FenwickTree::from_slice(&xs)
+    }
+}
+
+/// A Fenwick tree which supports combining a value into every element of a
+/// range and querying single elements.
+pub struct RangeUpdateFenwickTree<Spec: InvertibleSpec> {
+    /// Fenwick tree over the values combined in at the start of each range.
+    starts: FenwickTree<Spec>,
+    /// Fenwick tree over the values combined in at the end of each range. These
+    /// are taken out of 'starts' when querying rather than stored as inverses,
+    /// so unsigned sums never go below zero.
+    ends: FenwickTree<Spec>,
+}
+
+impl<Spec: InvertibleSpec + Default> RangeUpdateFenwickTree<Spec> {
+    /// Create a Fenwick tree over 'n' values, each set to 'Spec::default()'.
+    ///
+    /// Time complexity: O(n)
+    pub fn new(n: usize) -> Self {
+        RangeUpdateFenwickTree {
+            starts: FenwickTree::new(n),
+            ends: FenwickTree::new(n),
+        }
+    }
+}
+
+impl<Spec: InvertibleSpec + Clone> RangeUpdateFenwickTree<Spec> {
+    /// Create a Fenwick tree over 'n' values using specification 'spec'. Each
+    /// value is set to 'spec.default()'.
+    ///
+    /// Time complexity: O(n)
+    pub fn with_spec(spec: Spec, n: usize) -> Self {
+        RangeUpdateFenwickTree {
+            starts: FenwickTree::with_spec(spec.clone(), n),
+            ends: FenwickTree::with_spec(spec, n),
+        }
+    }
+}
+
+impl<Spec: InvertibleSpec> RangeUpdateFenwickTree<Spec> {
+    /// The number of values the Fenwick tree is built over.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// True if the Fenwick tree is built over zero values, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.starts.is_empty()
+    }
+
+    /// Combine 'x' into every value in the range '[l,r)'.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn add(&mut self, range: Range<usize>, x: Spec::T) {
+        let Range { start, end } = range;
+        assert!(
+            start <= end && end <= self.len(),
+            "range {}..{} out of bounds",
+            start,
+            end
+        );
+        if start == end {
+            return;
+        }
+        self.starts.add(start, x.clone());
+        if end < self.len() {
+            self.ends.add(end, x);
+        }
+    }
+
+    /// The value at index 'i'.
+    ///
+    /// Panics if 'i' is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn get(&self, i: usize) -> Spec::T {
+        assert!(i < self.len(), "index {} out of bounds", i);
+        let spec = self.starts.spec();
+        spec.uncombine(self.starts.prefix(i + 1), self.ends.prefix(i + 1))
+    }
+}
+
+/// A Fenwick tree which supports adding a value to every element of a range and
+/// querying range sums.
+pub struct RangeFenwickTree<T: Num + FromPrimitive + Clone> {
+    /// Fenwick tree over the values added at the start 'i' of each range.
+    starts: FenwickTree<SumSpec<T>>,
+    /// Fenwick tree over each value added at the start of a range times 'i'.
+    weighted_starts: FenwickTree<SumSpec<T>>,
+    /// Fenwick tree over the values added up to the end 'i' of each range.
+    /// These are subtracted when querying rather than stored negated, so
+    /// unsigned sums never go below zero.
+    ends: FenwickTree<SumSpec<T>>,
+    /// Fenwick tree over each value added up to the end of a range times 'i'.
+    weighted_ends: FenwickTree<SumSpec<T>>,
+}
+
+impl<T: Num + FromPrimitive + Clone> RangeFenwickTree<T> {
+    /// Create a Fenwick tree over 'n' values, each set to zero.
+    ///
+    /// Time complexity: O(n)
+    pub fn new(n: usize) -> Self {
+        RangeFenwickTree {
+            starts: FenwickTree::new(n),
+            weighted_starts: FenwickTree::new(n),
+            ends: FenwickTree::new(n),
+            weighted_ends: FenwickTree::new(n),
+        }
+    }
+
+    /// The number of values the Fenwick tree is built over.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// True if the Fenwick tree is built over zero values, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.starts.is_empty()
+    }
+
+    /// Convert index 'i' to 'T'.
+    fn index(i: usize) -> T {
+        T::from_usize(i).expect("index not representable")
+    }
+
+    /// Add 'x' to every value in the range '[l,r)'.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn add(&mut self, range: Range<usize>, x: T) {
+        let Range { start, end } = range;
+        assert!(
+            start <= end && end <= self.len(),
+            "range {}..{} out of bounds",
+            start,
+            end
+        );
+        if start == end {
+            return;
+        }
+        self.starts.add(start, x.clone());
+        self.weighted_starts
+            .add(start, x.clone() * RangeFenwickTree::index(start));
+        if end < self.len() {
+            self.ends.add(end, x.clone());
+            self.weighted_ends
+                .add(end, x * RangeFenwickTree::index(end));
+        }
+    }
+
+    /// The sum of the prefix '[0,r)'.
+    ///
+    /// Panics if 'r' is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn prefix(&self, r: usize) -> T {
+        // A value added at index 'i < r' contributes to 'r - i' elements of the
+        // prefix. Every range's end comes after its start, so the sum over
+        // starts is at least the sum over ends.
+        let r_index: T = RangeFenwickTree::index(r);
+        let started = self.starts.prefix(r) * r_index.clone() - self.weighted_starts.prefix(r);
+        let ended = self.ends.prefix(r) * r_index - self.weighted_ends.prefix(r);
+        started - ended
+    }
+
+    /// The sum of the values in the range '[l,r)'.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn query(&self, range: Range<usize>) -> T {
+        let Range { start, end } = range;
+        assert!(start <= end, "range {}..{} out of bounds", start, end);
+        self.prefix(end) - self.prefix(start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FenwickTree, RangeFenwickTree, RangeUpdateFenwickTree};
+    use crate::segment_tree::{SumSpec, XorSpec};
+
+    #[test]
+    fn it_answers_range_queries() {
+        let mut xs = vec![5, 3, 8, 6, 1, 0, 7, 2, 9];
+        let mut t: FenwickTree<SumSpec<i32>> = xs.iter().cloned().collect();
+        for step in 0..3 {
+            for l in 0..=xs.len() {
+                assert_eq!(t.prefix(l), xs[..l].iter().sum::<i32>());
+                for r in l..=xs.len() {
+                    assert_eq!(t.query(l..r), xs[l..r].iter().sum::<i32>());
+                }
+            }
+            t.update(step * 3, -4);
+            xs[step * 3] = -4;
+            t.add(step * 2 + 1, 10);
+            xs[step * 2 + 1] += 10;
+        }
+    }
+
+    #[test]
+    fn it_supports_xor() {
+        let xs = [0b101u32, 0b011, 0b110, 0b111];
+        let mut t = FenwickTree::<XorSpec<u32>>::from_slice(&xs);
+        assert_eq!(t.query(1..3), 0b101);
+        t.update(2, 0b001);
+        assert_eq!(t.get(2), 0b001);
+        assert_eq!(t.query(0..3), 0b111);
+        assert_eq!(t.query(0..4), 0);
+    }
+
+    #[test]
+    fn it_finds_lower_bound() {
+        let xs = [2u32, 0, 3, 1, 0, 4];
+        let t = FenwickTree::<SumSpec<u32>>::from_slice(&xs);
+        for x in 0..12 {
+            let expected = (0..xs.len())
+                .find(|&i| xs[..=i].iter().sum::<u32>() >= x)
+                .unwrap_or(xs.len());
+            assert_eq!(t.lower_bound(x), expected);
+        }
+    }
+
+    #[test]
+    fn it_decreases_unsigned_values() {
+        let mut t = FenwickTree::<SumSpec<u32>>::from_slice(&[4, 7, 1, 3]);
+        t.update(1, 2);
+        t.update(0, 0);
+        assert_eq!(t.get(1), 2);
+        assert_eq!(t.query(0..4), 6);
+        assert_eq!(t.query(1..3), 3);
+
+        let mut t = FenwickTree::<SumSpec<u8>>::from_slice(&[250, 0, 5]);
+        t.update(2, 1);
+        t.update(0, 254);
+        assert_eq!(t.query(0..3), 255);
+        assert_eq!(t.query(1..3), 1);
+
+        let mut point = RangeUpdateFenwickTree::<SumSpec<u32>>::new(4);
+        let mut range = RangeFenwickTree::<u32>::new(4);
+        point.add(1..3, 5);
+        range.add(1..3, 5);
+        assert_eq!(point.get(3), 0);
+        assert_eq!(range.query(2..4), 5);
+        assert_eq!(range.query(0..4), 10);
+    }
+
+    #[test]
+    fn it_updates_ranges() {
+        let mut xs = [0i64; 10];
+        let mut point = RangeUpdateFenwickTree::<SumSpec<i64>>::new(10);
+        let mut range = RangeFenwickTree::<i64>::new(10);
+        for (k, (l, r)) in [(2, 7), (0, 10), (5, 6), (9, 10), (3, 3), (0, 4)]
+            .iter()
+            .cloned()
+            .enumerate()
+        {
+            let x = k as i64 * 3 - 5;
+            point.add(l..r, x);
+            range.add(l..r, x);
+            xs[l..r].iter_mut().for_each(|y| *y += x);
+            for i in 0..10 {
+                assert_eq!(point.get(i), xs[i]);
+                for j in i..=10 {
+                    assert_eq!(range.query(i..j), xs[i..j].iter().sum::<i64>());
+                }
+            }
+        }
+    }
+}
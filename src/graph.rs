@@ -1,6 +1,8 @@
 //! Algorithms on graphs.
 
-use num::Zero;
+use crate::heap::radix::RadixHeap;
+use crate::heap::{AddressablePriorityQueue, MinPriorityQueue};
+use num::{PrimInt, Unsigned, Zero};
 use petgraph::stable_graph::IndexType;
 use petgraph::visit::{EdgeRef, IntoEdges, NodeCount, NodeIndexable};
 use std::cmp::Reverse;
@@ -19,15 +21,53 @@ where
     G: IntoEdges + NodeCount + NodeIndexable,
     G::NodeId: Ord + IndexType,
     G::EdgeWeight: Add<G::EdgeWeight> + Ord + Zero + Copy,
+{
+    shortest_distance_with::<G, BinaryHeap<Reverse<(G::EdgeWeight, G::NodeId)>>>(g, s)
+}
+
+/// Compute the shortest distance in a graph with unsigned integer edge weights
+/// from some source `s` to every vertex in the graph, using a radix heap
+/// instead of a comparison-based heap.
+///
+/// Returns a vector with the distance to each vertex or `None` if vertex cannot
+/// be reached. Distances must fit in the edge weight type.
+///
+/// Time complexity: `O(|E| + |V| log W)`, for `W` the number of bits of the
+/// edge weights.
+pub fn shortest_distance_radix<G>(g: G, s: G::NodeId) -> Vec<Option<G::EdgeWeight>>
+where
+    G: IntoEdges + NodeCount + NodeIndexable,
+    G::NodeId: IndexType,
+    G::EdgeWeight: PrimInt + Unsigned,
+{
+    shortest_distance_with::<G, RadixHeap<G::EdgeWeight, G::NodeId>>(g, s)
+}
+
+/// Compute the shortest distance in a graph with non-negative graphs from some
+/// source `s` to every vertex in the graph, using the priority queue `Q`.
+///
+/// Vertices are inserted again when their distance improves and stale entries
+/// are skipped when extracted, so `Q` needs no decrease-key operation.
+///
+/// Returns a vector with the distance to each vertex or `None` if vertex cannot
+/// be reached.
+///
+/// Time complexity: `O(|E|)` insertions and extractions on `Q`.
+pub fn shortest_distance_with<G, Q>(g: G, s: G::NodeId) -> Vec<Option<G::EdgeWeight>>
+where
+    G: IntoEdges + NodeCount + NodeIndexable,
+    G::NodeId: IndexType,
+    G::EdgeWeight: Add<G::EdgeWeight> + Ord + Zero + Copy,
+    Q: MinPriorityQueue<G::EdgeWeight, G::NodeId> + Default,
 {
     let n = g.node_count();
     let mut distance: Vec<Option<G::EdgeWeight>> = vec![None; n];
-    let mut queue = BinaryHeap::new();
+    let mut queue = Q::default();
 
     distance[s.index()] = Some(num::zero());
-    queue.push(Reverse((num::zero(), s)));
+    queue.insert(num::zero(), s);
 
-    while let Some(Reverse((d, u))) = queue.pop() {
+    while let Some((d, u)) = queue.extract_min() {
         if let Some(cur_d) = distance[u.index()] {
             if cur_d < d {
                 continue;
@@ -42,7 +82,56 @@ where
             };
             if should_queue {
                 distance[e.target().index()] = Some(new_d);
-                queue.push(Reverse((new_d, e.target())));
+                queue.insert(new_d, e.target());
+            }
+        }
+    }
+
+    distance
+}
+
+/// Compute the shortest distance in a graph with non-negative graphs from some
+/// source `s` to every vertex in the graph, using the priority queue `Q`.
+///
+/// Each vertex is inserted at most once and its key is decreased when its
+/// distance improves, so `Q` never holds stale entries.
+///
+/// Returns a vector with the distance to each vertex or `None` if vertex cannot
+/// be reached.
+///
+/// Time complexity: `O(|V|)` insertions and extractions and `O(|E|)` key
+/// decreases on `Q`.
+pub fn shortest_distance_addressable<G, Q>(g: G, s: G::NodeId) -> Vec<Option<G::EdgeWeight>>
+where
+    G: IntoEdges + NodeCount + NodeIndexable,
+    G::NodeId: IndexType,
+    G::EdgeWeight: Add<G::EdgeWeight> + Ord + Zero + Copy,
+    Q: AddressablePriorityQueue<G::EdgeWeight, G::NodeId> + Default,
+{
+    let n = g.node_count();
+    let mut distance: Vec<Option<G::EdgeWeight>> = vec![None; n];
+    let mut handles: Vec<Option<Q::Handle>> = vec![None; n];
+    let mut queue = Q::default();
+
+    distance[s.index()] = Some(num::zero());
+    handles[s.index()] = Some(queue.insert_with_handle(num::zero(), s));
+
+    while let Some((d, u)) = queue.extract_min() {
+        for e in g.edges(u) {
+            let v = e.target().index();
+            let new_d = d + *e.weight();
+            // Vertices already extracted have a distance of at most `d`, so
+            // only queued vertices have their key decreased.
+            match distance[v] {
+                None => {
+                    distance[v] = Some(new_d);
+                    handles[v] = Some(queue.insert_with_handle(new_d, e.target()));
+                }
+                Some(cur_d) if new_d < cur_d => {
+                    distance[v] = Some(new_d);
+                    queue.decrease_key(handles[v].unwrap(), new_d);
+                }
+                Some(_) => {}
             }
         }
     }
@@ -102,4 +191,148 @@ mod tests {
             )
         }
     }
+
+    mod shortest_distance_radix {
+        use super::super::{shortest_distance, shortest_distance_radix};
+        use crate::test_util::Lcg;
+        use petgraph::graph::{DiGraph, Graph, UnGraph};
+
+        #[test]
+        fn two_component_graph() {
+            let g: UnGraph<(), u32> =
+                Graph::from_edges(&[(0, 1, 5), (1, 2, 3), (0, 2, 7), (3, 4, 0)]);
+            assert_eq!(
+                shortest_distance_radix(&g, 0.into()),
+                vec![Some(0), Some(5), Some(7), None, None]
+            );
+        }
+
+        #[test]
+        fn matches_binary_heap() {
+            let mut rng = Lcg::new(3);
+            let mut next = || rng.next_u64() as u32;
+            let edges: Vec<(u32, u32, u32)> = (0..2000)
+                .map(|_| (next() % 300, next() % 300, next() % 1000))
+                .collect();
+            let g: DiGraph<(), u32> = Graph::from_edges(&edges);
+            for s in 0..5 {
+                assert_eq!(
+                    shortest_distance_radix(&g, s.into()),
+                    shortest_distance(&g, s.into())
+                );
+            }
+        }
+    }
+
+    mod shortest_distance_with {
+        use super::super::{shortest_distance, shortest_distance_with};
+        use crate::heap::binomial::BinomialHeap;
+        use crate::heap::fibonacci::FibonacciHeap;
+        use crate::heap::indexed::IndexedHeap;
+        use crate::heap::leftist::LeftistHeap;
+        use crate::heap::min_max::MinMaxHeap;
+        use crate::heap::pairing::PairingHeap;
+        use crate::heap::radix::RadixHeap;
+        use crate::heap::{DaryHeap, Heap, MinComparator};
+        use crate::test_util::Lcg;
+        use petgraph::graph::{DiGraph, Graph, NodeIndex};
+
+        type Key = (u32, NodeIndex);
+
+        #[test]
+        fn matches_binary_heap() {
+            let mut rng = Lcg::new(11);
+            let mut next = || rng.next_u64() as u32;
+            let edges: Vec<(u32, u32, u32)> = (0..1000)
+                .map(|_| (next() % 150, next() % 150, next() % 100))
+                .collect();
+            let g: DiGraph<(), u32> = Graph::from_edges(&edges);
+            let s = 0.into();
+            let expected = shortest_distance(&g, s);
+            assert_eq!(
+                shortest_distance_with::<_, Heap<Key, MinComparator>>(&g, s),
+                expected
+            );
+            assert_eq!(
+                shortest_distance_with::<_, DaryHeap<Key, 4, MinComparator>>(&g, s),
+                expected
+            );
+            assert_eq!(
+                shortest_distance_with::<_, IndexedHeap<Key, MinComparator>>(&g, s),
+                expected
+            );
+            assert_eq!(
+                shortest_distance_with::<_, PairingHeap<Key, MinComparator>>(&g, s),
+                expected
+            );
+            assert_eq!(
+                shortest_distance_with::<_, LeftistHeap<Key, MinComparator>>(&g, s),
+                expected
+            );
+            assert_eq!(
+                shortest_distance_with::<_, BinomialHeap<Key, MinComparator>>(&g, s),
+                expected
+            );
+            assert_eq!(
+                shortest_distance_with::<_, FibonacciHeap<Key, MinComparator>>(&g, s),
+                expected
+            );
+            assert_eq!(
+                shortest_distance_with::<_, MinMaxHeap<Key>>(&g, s),
+                expected
+            );
+            assert_eq!(
+                shortest_distance_with::<_, RadixHeap<u32, NodeIndex>>(&g, s),
+                expected
+            );
+        }
+    }
+
+    mod shortest_distance_addressable {
+        use super::super::{shortest_distance, shortest_distance_addressable};
+        use crate::heap::fibonacci::FibonacciHeap;
+        use crate::heap::indexed::IndexedHeap;
+        use crate::heap::MinComparator;
+        use crate::test_util::Lcg;
+        use petgraph::graph::{DiGraph, Graph, NodeIndex, UnGraph};
+
+        type Key = (u32, NodeIndex);
+
+        #[test]
+        fn two_component_graph() {
+            let g: UnGraph<(), u32> =
+                Graph::from_edges(&[(0, 1, 5), (1, 2, 3), (0, 2, 9), (3, 4, 0)]);
+            assert_eq!(
+                shortest_distance_addressable::<_, IndexedHeap<Key, MinComparator>>(&g, 0.into()),
+                vec![Some(0), Some(5), Some(8), None, None]
+            );
+        }
+
+        #[test]
+        fn matches_binary_heap() {
+            let mut rng = Lcg::new(5);
+            let mut next = || rng.next_u64() as u32;
+            let edges: Vec<(u32, u32, u32)> = (0..1500)
+                .map(|_| (next() % 200, next() % 200, next() % 100))
+                .collect();
+            let g: DiGraph<(), u32> = Graph::from_edges(&edges);
+            for s in 0..3 {
+                let expected = shortest_distance(&g, s.into());
+                assert_eq!(
+                    shortest_distance_addressable::<_, IndexedHeap<Key, MinComparator>>(
+                        &g,
+                        s.into()
+                    ),
+                    expected
+                );
+                assert_eq!(
+                    shortest_distance_addressable::<_, FibonacciHeap<Key, MinComparator>>(
+                        &g,
+                        s.into()
+                    ),
+                    expected
+                );
+            }
+        }
+    }
 }
@@ -1,134 +1,597 @@
-pub struct Heap<T: Ord> {
+use std::cmp::{min, Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut, Range};
+use std::{slice, vec};
+
+pub mod binomial;
+pub mod fibonacci;
+pub mod indexed;
+pub mod leftist;
+pub mod min_max;
+pub mod pairing;
+pub mod radix;
+pub mod select;
+
+/// A priority queue of values with keys, always extracting a value with the
+/// smallest key. Used by graph algorithms to swap queue implementations.
+pub trait MinPriorityQueue<K, V> {
+    /// Insert 'value' with priority 'key'.
+    fn insert(&mut self, key: K, value: V);
+
+    /// Remove a value with the smallest key along with its key, or 'None' if
+    /// the queue is empty.
+    fn extract_min(&mut self) -> Option<(K, V)>;
+}
+
+/// A priority queue whose values can be given a smaller key after they are
+/// inserted, through the handle returned on insertion. Lets graph algorithms
+/// update a queued vertex instead of inserting it again.
+pub trait AddressablePriorityQueue<K, V>: MinPriorityQueue<K, V> {
+    type Handle: Copy;
+
+    /// Insert 'value' with priority 'key'. Returns the handle of the value,
+    /// valid until the value is extracted.
+    fn insert_with_handle(&mut self, key: K, value: V) -> Self::Handle;
+
+    /// Replace the key of the value of 'handle' with 'key', which must not be
+    /// larger than its current key.
+    fn decrease_key(&mut self, handle: Self::Handle, key: K);
+}
+
+impl<K: Ord, V: Ord> MinPriorityQueue<K, V> for BinaryHeap<Reverse<(K, V)>> {
+    fn insert(&mut self, key: K, value: V) {
+        self.push(Reverse((key, value)));
+    }
+
+    fn extract_min(&mut self) -> Option<(K, V)> {
+        self.pop().map(|Reverse(x)| x)
+    }
+}
+
+/// Decides the order of elements in a heap. The heap keeps the greatest element
+/// according to 'compare' at its root.
+///
+/// Any closure 'Fn(&T, &T) -> Ordering' is a comparator.
+pub trait Comparator<T> {
+    fn compare(&self, a: &T, b: &T) -> Ordering;
+}
+
+/// Comparator using the natural order of elements, giving a max-heap.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MaxComparator;
+
+impl<T: Ord> Comparator<T> for MaxComparator {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+/// Comparator reversing the natural order of elements, giving a min-heap.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MinComparator;
+
+impl<T: Ord> Comparator<T> for MinComparator {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering> Comparator<T> for F {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self(a, b)
+    }
+}
+
+/// Comparator ordering elements by the key 'key' extracts from them, where
+/// keys are ordered by the comparator 'cmp'.
+#[derive(Clone, Copy, Debug)]
+pub struct KeyComparator<F, C = MaxComparator> {
+    key: F,
+    cmp: C,
+}
+
+impl<F, C> KeyComparator<F, C> {
+    /// Create a comparator ordering elements by 'key' using 'cmp' for keys.
+    pub fn new(key: F, cmp: C) -> Self {
+        KeyComparator { key, cmp }
+    }
+}
+
+impl<T, K, F: Fn(&T) -> K, C: Comparator<K>> Comparator<T> for KeyComparator<F, C> {
+    fn compare(&self, a: &T, b: &T) -> Ordering {
+        self.cmp.compare(&(self.key)(a), &(self.key)(b))
+    }
+}
+
+/// A heap where each vertex has up to 'D' children, ordered by the comparator
+/// 'C' and a max-heap by default. A larger arity makes the heap shallower,
+/// speeding up 'push' at the cost of more comparisons per 'pop'.
+pub struct DaryHeap<T, const D: usize, C = MaxComparator> {
     xs: Vec<T>,
+    cmp: C,
 }
 
-impl<T: Ord> Heap<T> {
-    /// The parent of index 'i' in a perfectly balanced binary tree or 'None' if
-    /// 'i' is the root.
-    fn parent(i: usize) -> Option<usize> {
-        if i == 0 {
-            return None;
-        } else {
-            return Some((i - 1) / 2);
-        }
+/// A binary heap ordered by the comparator 'C', a max-heap by default.
+pub type Heap<T, C = MaxComparator> = DaryHeap<T, 2, C>;
+
+impl<T, const D: usize, C: Comparator<T> + Default> Default for DaryHeap<T, D, C> {
+    fn default() -> Self {
+        DaryHeap::with_comparator(C::default())
     }
+}
 
-    /// The left child index 'i' in a perfectly balanced binary tree or 'None'
-    /// if this access is out of bounds.
-    fn left_child(&self, i: usize) -> Option<usize> {
-        let j = 2 * i + 1;
-        if j >= self.xs.len() {
-            return None;
-        } else {
-            return Some(j);
+impl<T: Ord, const D: usize> DaryHeap<T, D> {
+    /// Create an empty max-heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn new() -> Self {
+        DaryHeap::with_comparator(MaxComparator)
+    }
+}
+
+impl<T: Ord, const D: usize> DaryHeap<T, D, MinComparator> {
+    /// Create an empty min-heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn new_min() -> Self {
+        DaryHeap::with_comparator(MinComparator)
+    }
+}
+
+impl<T, K: Ord, F: Fn(&T) -> K, const D: usize> DaryHeap<T, D, KeyComparator<F>> {
+    /// Create an empty heap where the element with the largest key is at the
+    /// root.
+    ///
+    /// Time complexity: O(1)
+    pub fn by_key(key: F) -> Self {
+        DaryHeap::with_comparator(KeyComparator::new(key, MaxComparator))
+    }
+}
+
+impl<T, K: Ord, F: Fn(&T) -> K, const D: usize> DaryHeap<T, D, KeyComparator<F, MinComparator>> {
+    /// Create an empty heap where the element with the smallest key is at the
+    /// root.
+    ///
+    /// Time complexity: O(1)
+    pub fn min_by_key(key: F) -> Self {
+        DaryHeap::with_comparator(KeyComparator::new(key, MinComparator))
+    }
+}
+
+/// The parent of index 'i' in a perfectly balanced 'D'-ary tree or 'None' if
+/// 'i' is the root.
+fn parent<const D: usize>(i: usize) -> Option<usize> {
+    if i == 0 {
+        None
+    } else {
+        Some((i - 1) / D)
+    }
+}
+
+/// The child indices of 'i' in a perfectly balanced 'D'-ary tree with 'n'
+/// vertices, empty if 'i' is a leaf.
+fn children<const D: usize>(i: usize, n: usize) -> Range<usize> {
+    min(D * i + 1, n)..min(D * i + D + 1, n)
+}
+
+/// Elements laid out as a 'D'-ary heap which are compared and swapped by
+/// index. Lets heaps which keep extra state per element, like 'IndexedHeap',
+/// share the sift operations.
+trait HeapSlots {
+    /// The number of elements.
+    fn len(&self) -> usize;
+
+    /// True if the element at index 'i' should be above the element at index
+    /// 'j'.
+    fn above(&self, i: usize, j: usize) -> bool;
+
+    /// Swap the elements at index 'i' and 'j'.
+    fn swap(&mut self, i: usize, j: usize);
+}
+
+/// A slice of elements ordered by a comparator.
+struct Ordered<'a, T, C> {
+    xs: &'a mut [T],
+    cmp: &'a C,
+}
+
+impl<'a, T, C: Comparator<T>> HeapSlots for Ordered<'a, T, C> {
+    fn len(&self) -> usize {
+        self.xs.len()
+    }
+
+    fn above(&self, i: usize, j: usize) -> bool {
+        self.cmp.compare(&self.xs[i], &self.xs[j]) == Ordering::Greater
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.xs.swap(i, j);
+    }
+}
+
+/// Move the element at index 'i' of the 'D'-ary heap 'slots' up until its
+/// parent is not below it. Returns the new index of the element.
+fn sift_up_slots<const D: usize, S: HeapSlots>(slots: &mut S, mut i: usize) -> usize {
+    while let Some(j) = parent::<D>(i) {
+        if !slots.above(i, j) {
+            break;
         }
+        slots.swap(i, j);
+        i = j;
     }
+    i
+}
 
-    /// The right child index 'i' in a perfectly balanced binary tree or 'None'
-    /// if this access is out of bounds.
-    fn right_child(&self, i: usize) -> Option<usize> {
-        let j = 2 * i + 2;
-        if j >= self.xs.len() {
-            return None;
-        } else {
-            return Some(j);
+/// Move the element at index 'i' of the 'D'-ary heap 'slots' down until no
+/// child is above it.
+fn sift_down_slots<const D: usize, S: HeapSlots>(slots: &mut S, mut i: usize) {
+    let n = slots.len();
+    loop {
+        let mut c = i;
+        for j in children::<D>(i, n) {
+            if slots.above(j, c) {
+                c = j;
+            }
+        }
+        if c == i {
+            break;
         }
+        slots.swap(i, c);
+        i = c;
     }
+}
 
-    /// Create an empty max-heap.
+/// Move the element at index 'i' of the 'D'-ary heap 'xs' up until its parent
+/// is not below it.
+fn sift_up<const D: usize, T, C: Comparator<T>>(xs: &mut [T], i: usize, cmp: &C) {
+    sift_up_slots::<D, _>(&mut Ordered { xs, cmp }, i);
+}
+
+/// Move the element at index 'i' of the 'D'-ary heap 'xs' down until no child
+/// is above it.
+fn sift_down<const D: usize, T, C: Comparator<T>>(xs: &mut [T], i: usize, cmp: &C) {
+    sift_down_slots::<D, _>(&mut Ordered { xs, cmp }, i);
+}
+
+/// Rearrange 'xs' into a 'D'-ary heap ordered by 'cmp' using Floyd's bottom-up
+/// construction.
+///
+/// Time complexity: O(n)
+fn heapify<const D: usize, T, C: Comparator<T>>(xs: &mut [T], cmp: &C) {
+    let n = xs.len();
+    for i in (0..n).rev().skip_while(|&i| children::<D>(i, n).is_empty()) {
+        sift_down::<D, T, C>(xs, i, cmp);
+    }
+}
+
+/// Sort the 'D'-ary heap 'xs' in place so that the greatest element according
+/// to 'cmp' is last.
+///
+/// Time complexity: O(n log n)
+fn sort_heap<const D: usize, T, C: Comparator<T>>(xs: &mut [T], cmp: &C) {
+    for end in (1..xs.len()).rev() {
+        xs.swap(0, end);
+        sift_down::<D, T, C>(&mut xs[..end], 0, cmp);
+    }
+}
+
+/// Sort 'xs' in ascending order in place.
+///
+/// Time complexity: O(n log n)
+pub fn heapsort<T: Ord>(xs: &mut [T]) {
+    heapsort_by(xs, MaxComparator);
+}
+
+/// Sort 'xs' in place in ascending order according to 'cmp'.
+///
+/// Time complexity: O(n log n)
+pub fn heapsort_by<T, C: Comparator<T>>(xs: &mut [T], cmp: C) {
+    heapify::<2, T, C>(xs, &cmp);
+    sort_heap::<2, T, C>(xs, &cmp);
+}
+
+impl<T, const D: usize, C: Comparator<T>> DaryHeap<T, D, C> {
+    /// Create an empty heap ordered by 'cmp'.
+    ///
+    /// Panics if the arity 'D' is less than 2.
     ///
     /// Time complexity: O(1)
-    pub fn new() -> Self {
-        Heap { xs: Vec::new() }
+    pub fn with_comparator(cmp: C) -> Self {
+        assert!(D >= 2, "arity {} is less than 2", D);
+        DaryHeap {
+            xs: Vec::new(),
+            cmp,
+        }
+    }
+
+    /// Create a heap ordered by 'cmp' containing the elements of 'xs'.
+    ///
+    /// Panics if the arity 'D' is less than 2.
+    ///
+    /// Time complexity: O(n)
+    pub fn from_vec_with_comparator(mut xs: Vec<T>, cmp: C) -> Self {
+        assert!(D >= 2, "arity {} is less than 2", D);
+        heapify::<D, T, C>(&mut xs, &cmp);
+        DaryHeap { xs, cmp }
     }
 
     /// True if heap is empty, false otherwise.
     ///
     /// Time complexity: O(1)
+    #[deprecated(note = "use 'is_empty' instead")]
     pub fn empty(&self) -> bool {
-        self.xs.len() == 0
+        self.is_empty()
+    }
+
+    /// True if heap is empty, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.xs.is_empty()
     }
 
-    /// Insert an element into the max-heap.
+    /// The number of elements in the heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.xs.len()
+    }
+
+    /// Remove all elements from the heap.
+    ///
+    /// Time complexity: O(n)
+    pub fn clear(&mut self) {
+        self.xs.clear();
+    }
+
+    /// Insert an element into the heap.
     ///
     /// Time complexity: O(log n)
     pub fn push(&mut self, x: T) {
         self.xs.push(x);
-        let mut i = self.xs.len() - 1;
-        while let Some(j) = Heap::<T>::parent(i) {
-            if self.xs[i] > self.xs[j] {
-                self.xs.swap(i, j);
-                i = j;
-            } else {
-                break;
-            }
-        }
+        let i = self.xs.len() - 1;
+        sift_up::<D, T, C>(&mut self.xs, i, &self.cmp);
     }
 
-    /// Get a reference to the largest element in the heap or 'None' if heap is
-    /// empty.
+    /// Get a reference to the greatest element in the heap according to the
+    /// comparator or 'None' if heap is empty.
     ///
     /// Time complexity: O(1)
     pub fn peek(&self) -> Option<&T> {
-        if self.xs.len() == 0 {
-            return None;
+        if self.xs.is_empty() {
+            None
         } else {
-            return Some(&self.xs[0]);
+            Some(&self.xs[0])
         }
     }
 
-    /// Remove the largest element in the heap. Returns the removed element or
-    /// 'None' in case the heap was empty.
+    /// Remove the greatest element in the heap according to the comparator.
+    /// Returns the removed element or 'None' in case the heap was empty.
     ///
     /// Time complexity: O(log n)
     pub fn pop(&mut self) -> Option<T> {
-        if self.xs.len() == 0 {
-            return None;
+        if self.xs.is_empty() {
+            None
         } else {
             let n = self.xs.len();
             self.xs.swap(0, n - 1);
             let popped_elem = self.xs.pop();
-
-            let mut i = 0;
-            while let Some(l) = self.left_child(i) {
-                if let Some(r) = self.right_child(i) {
-                    if self.xs[l] < self.xs[r] {
-                        if self.xs[i] < self.xs[r] {
-                            self.xs.swap(i, r);
-                            i = r;
-                        } else {
-                            break;
-                        }
-                    } else {
-                        if self.xs[i] < self.xs[l] {
-                            self.xs.swap(i, l);
-                            i = l;
-                        } else {
-                            break;
-                        }
-                    }
-                } else {
-                    if self.xs[i] < self.xs[l] {
-                        self.xs.swap(i, l);
-                        i = l;
-                    } else {
-                        break;
-                    }
-                }
-            }
+            sift_down::<D, T, C>(&mut self.xs, 0, &self.cmp);
             popped_elem
         }
     }
+
+    /// Get a mutable reference to the greatest element in the heap according
+    /// to the comparator or 'None' if heap is empty. The heap is restored when
+    /// the returned guard is dropped.
+    ///
+    /// Time complexity: O(log n) once the guard is dropped
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T, D, C>> {
+        if self.xs.is_empty() {
+            None
+        } else {
+            Some(PeekMut {
+                heap: self,
+                sift: false,
+            })
+        }
+    }
+
+    /// Iterate over the elements of the heap in arbitrary order.
+    ///
+    /// Time complexity: O(1)
+    pub fn iter(&self) -> slice::Iter<'_, T> {
+        self.xs.iter()
+    }
+
+    /// Remove all elements from the heap, returning them in arbitrary order.
+    ///
+    /// Time complexity: O(n)
+    pub fn drain(&mut self) -> vec::Drain<'_, T> {
+        self.xs.drain(..)
+    }
+
+    /// Remove all elements from the heap, returning them in the order 'pop'
+    /// would. Elements which are not iterated over are removed once the
+    /// iterator is dropped.
+    ///
+    /// Time complexity: O(log n) per element
+    pub fn drain_sorted(&mut self) -> DrainSorted<'_, T, D, C> {
+        DrainSorted { heap: self }
+    }
+
+    /// Iterate over the elements of the heap in the order 'pop' would return
+    /// them.
+    ///
+    /// Time complexity: O(log n) per element
+    pub fn into_iter_sorted(self) -> IntoIterSorted<T, D, C> {
+        IntoIterSorted { heap: self }
+    }
+
+    /// The elements of the heap in arbitrary order.
+    ///
+    /// Time complexity: O(1)
+    pub fn into_vec(self) -> Vec<T> {
+        self.xs
+    }
+
+    /// The elements of the heap in ascending order according to the
+    /// comparator, so the element 'peek' returns is last.
+    ///
+    /// Time complexity: O(n log n)
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        sort_heap::<D, T, C>(&mut self.xs, &self.cmp);
+        self.xs
+    }
+}
+
+impl<T, const D: usize, C: Comparator<T> + Default> From<Vec<T>> for DaryHeap<T, D, C> {
+    fn from(xs: Vec<T>) -> Self {
+        DaryHeap::from_vec_with_comparator(xs, C::default())
+    }
+}
+
+impl<T, const D: usize, C: Comparator<T> + Default> FromIterator<T> for DaryHeap<T, D, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        DaryHeap::from(iter.into_iter().collect::<Vec<T>>())
+    }
+}
+
+impl<T, const D: usize, C: Comparator<T>> Extend<T> for DaryHeap<T, D, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+impl<K: Ord, V: Ord, const D: usize> MinPriorityQueue<K, V> for DaryHeap<(K, V), D, MinComparator> {
+    fn insert(&mut self, key: K, value: V) {
+        self.push((key, value));
+    }
+
+    fn extract_min(&mut self) -> Option<(K, V)> {
+        self.pop()
+    }
+}
+
+impl<T, const D: usize, C> IntoIterator for DaryHeap<T, D, C> {
+    type Item = T;
+    type IntoIter = vec::IntoIter<T>;
+
+    /// Iterate over the elements of the heap in arbitrary order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.xs.into_iter()
+    }
+}
+
+impl<'a, T, const D: usize, C> IntoIterator for &'a DaryHeap<T, D, C> {
+    type Item = &'a T;
+    type IntoIter = slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.xs.iter()
+    }
+}
+
+/// A mutable reference to the greatest element of a heap, created by
+/// 'Heap::peek_mut'. The element is sifted down when the guard is dropped if
+/// it was mutably borrowed.
+pub struct PeekMut<'a, T, const D: usize, C: Comparator<T>> {
+    heap: &'a mut DaryHeap<T, D, C>,
+    sift: bool,
+}
+
+impl<'a, T, const D: usize, C: Comparator<T>> PeekMut<'a, T, D, C> {
+    /// Remove the peeked element from the heap.
+    ///
+    /// Time complexity: O(log n)
+    pub fn pop(mut this: PeekMut<'a, T, D, C>) -> T {
+        // 'pop' restores the heap itself, so dropping the guard must not.
+        this.sift = false;
+        this.heap.pop().unwrap()
+    }
+}
+
+impl<'a, T, const D: usize, C: Comparator<T>> Deref for PeekMut<'a, T, D, C> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.heap.xs[0]
+    }
+}
+
+impl<'a, T, const D: usize, C: Comparator<T>> DerefMut for PeekMut<'a, T, D, C> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.sift = true;
+        &mut self.heap.xs[0]
+    }
+}
+
+impl<'a, T, const D: usize, C: Comparator<T>> Drop for PeekMut<'a, T, D, C> {
+    fn drop(&mut self) {
+        if self.sift {
+            sift_down::<D, T, C>(&mut self.heap.xs, 0, &self.heap.cmp);
+        }
+    }
+}
+
+/// An iterator removing the elements of a heap in order, created by
+/// 'Heap::drain_sorted'.
+pub struct DrainSorted<'a, T, const D: usize, C: Comparator<T>> {
+    heap: &'a mut DaryHeap<T, D, C>,
+}
+
+impl<'a, T, const D: usize, C: Comparator<T>> Iterator for DrainSorted<'a, T, D, C> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.heap.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.len(), Some(self.heap.len()))
+    }
+}
+
+impl<'a, T, const D: usize, C: Comparator<T>> ExactSizeIterator for DrainSorted<'a, T, D, C> {}
+
+impl<'a, T, const D: usize, C: Comparator<T>> Drop for DrainSorted<'a, T, D, C> {
+    fn drop(&mut self) {
+        self.heap.clear();
+    }
+}
+
+/// An iterator over the elements of a heap in order, created by
+/// 'Heap::into_iter_sorted'.
+pub struct IntoIterSorted<T, const D: usize, C: Comparator<T>> {
+    heap: DaryHeap<T, D, C>,
+}
+
+impl<T, const D: usize, C: Comparator<T>> Iterator for IntoIterSorted<T, D, C> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.heap.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.len(), Some(self.heap.len()))
+    }
 }
 
+impl<T, const D: usize, C: Comparator<T>> ExactSizeIterator for IntoIterSorted<T, D, C> {}
+
 #[cfg(test)]
 mod tests {
-    use super::Heap;
+    use super::{heapsort, heapsort_by, Comparator, DaryHeap, Heap, MinComparator, PeekMut};
+    use crate::test_util::Lcg;
 
     #[test]
     fn it_constructs_empty() {
         let mut heap = Heap::<i32>::new();
-        assert!(heap.empty());
+        assert!(heap.is_empty());
         assert_eq!(heap.peek(), None);
         assert_eq!(heap.pop(), None);
     }
@@ -140,7 +603,7 @@ mod tests {
         assert_eq!(heap.xs, vec![0]);
         assert_eq!(heap.peek(), Some(0).as_ref());
         assert_eq!(heap.pop(), Some(0));
-        assert!(heap.empty())
+        assert!(heap.is_empty())
     }
 
     #[test]
@@ -167,4 +630,215 @@ mod tests {
         assert_eq!(heap.pop(), Some(0));
         assert_eq!(heap.pop(), Some(0));
     }
+
+    /// Pop every element of 'heap' in order.
+    fn drain<T, C: Comparator<T>>(heap: &mut Heap<T, C>) -> Vec<T> {
+        let mut xs = Vec::new();
+        while let Some(x) = heap.pop() {
+            xs.push(x);
+        }
+        xs
+    }
+
+    #[test]
+    fn it_supports_min_heap() {
+        let mut heap = Heap::new_min();
+        for &x in [5, 1, 4, 4, 9, 2, 7].iter() {
+            heap.push(x);
+        }
+        assert_eq!(heap.peek(), Some(&1));
+        assert_eq!(drain(&mut heap), vec![1, 2, 4, 4, 5, 7, 9]);
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Task {
+        name: &'static str,
+        cost: u32,
+    }
+
+    #[test]
+    fn it_orders_by_key() {
+        let tasks = || {
+            vec![
+                Task { name: "b", cost: 3 },
+                Task { name: "a", cost: 1 },
+                Task { name: "c", cost: 2 },
+            ]
+        };
+        let mut heap = Heap::min_by_key(|t: &Task| t.cost);
+        for t in tasks() {
+            heap.push(t);
+        }
+        let names: Vec<_> = drain(&mut heap).iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["a", "c", "b"]);
+
+        let mut heap = Heap::by_key(|t: &Task| t.cost);
+        for t in tasks() {
+            heap.push(t);
+        }
+        let names: Vec<_> = drain(&mut heap).iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["b", "c", "a"]);
+    }
+
+    #[test]
+    fn it_orders_by_custom_comparator() {
+        // Shortest first, breaking ties alphabetically.
+        let mut heap = Heap::with_comparator(|a: &&str, b: &&str| {
+            b.len().cmp(&a.len()).then_with(|| b.cmp(a))
+        });
+        for &s in ["ccc", "b", "aa", "a", "bb"].iter() {
+            heap.push(s);
+        }
+        assert_eq!(drain(&mut heap), vec!["a", "b", "aa", "bb", "ccc"]);
+
+        let mut heap: Heap<i32, MinComparator> = Default::default();
+        heap.push(3);
+        heap.push(-1);
+        assert_eq!(heap.pop(), Some(-1));
+    }
+
+    #[test]
+    fn it_heapifies() {
+        let xs = vec![5, 1, 4, 4, 9, 2, 7, 0, 3];
+        let mut heap: Heap<i32> = Heap::from(xs.clone());
+        assert_eq!(heap.len(), xs.len());
+        assert_eq!(heap.peek(), Some(&9));
+        let mut sorted = xs.clone();
+        sorted.sort_unstable_by(|a, b| b.cmp(a));
+        assert_eq!(drain(&mut heap), sorted);
+
+        let heap: Heap<i32, MinComparator> = xs.iter().cloned().collect();
+        assert_eq!(heap.peek(), Some(&0));
+        let mut all = heap.into_vec();
+        all.sort_unstable();
+        sorted.reverse();
+        assert_eq!(all, sorted);
+    }
+
+    #[test]
+    fn it_converts_into_sorted_vec() {
+        let xs = vec![5, 1, 4, 4, 9, 2, 7, 0, 3];
+        let mut sorted = xs.clone();
+        sorted.sort_unstable();
+        let heap: Heap<i32> = xs.iter().cloned().collect();
+        assert_eq!(heap.into_sorted_vec(), sorted);
+
+        let mut heap = Heap::new_min();
+        heap.extend(xs);
+        sorted.reverse();
+        assert_eq!(heap.into_sorted_vec(), sorted);
+    }
+
+    #[test]
+    fn it_clears() {
+        let mut heap: Heap<i32> = vec![3, 1, 2].into();
+        heap.clear();
+        assert!(heap.is_empty());
+        heap.push(4);
+        assert_eq!(heap.pop(), Some(4));
+    }
+
+    #[test]
+    fn it_sorts_slices() {
+        for n in 0..40 {
+            let mut xs: Vec<u32> = (0..n).map(|i| (i * 37 + 11) % 17).collect();
+            let mut expected = xs.clone();
+            expected.sort_unstable();
+            heapsort(&mut xs);
+            assert_eq!(xs, expected);
+
+            heapsort_by(&mut xs, |a: &u32, b: &u32| b.cmp(a));
+            expected.reverse();
+            assert_eq!(xs, expected);
+        }
+    }
+
+    #[test]
+    fn it_iterates() {
+        let heap: Heap<i32> = vec![5, 1, 4, 9, 2].into();
+        let mut xs: Vec<i32> = heap.iter().cloned().collect();
+        xs.sort_unstable();
+        assert_eq!(xs, vec![1, 2, 4, 5, 9]);
+        assert_eq!((&heap).into_iter().count(), 5);
+        assert_eq!(heap.into_iter().sum::<i32>(), 21);
+    }
+
+    #[test]
+    fn it_drains() {
+        let mut heap: Heap<i32> = vec![5, 1, 4, 9, 2].into();
+        let mut xs: Vec<i32> = heap.drain().collect();
+        xs.sort_unstable();
+        assert_eq!(xs, vec![1, 2, 4, 5, 9]);
+        assert!(heap.is_empty());
+
+        heap.extend(vec![5, 1, 4, 9, 2]);
+        let sorted = heap.drain_sorted();
+        assert_eq!(sorted.len(), 5);
+        assert_eq!(sorted.take(2).collect::<Vec<_>>(), vec![9, 5]);
+        assert!(heap.is_empty());
+
+        let heap: Heap<i32, MinComparator> = vec![5, 1, 4, 9, 2].into();
+        let sorted: Vec<i32> = heap.into_iter_sorted().collect();
+        assert_eq!(sorted, vec![1, 2, 4, 5, 9]);
+    }
+
+    #[test]
+    fn it_adjusts_top_in_place() {
+        let mut heap: Heap<i32> = vec![5, 1, 4, 9, 2].into();
+        *heap.peek_mut().unwrap() = 3;
+        assert_eq!(heap.peek(), Some(&5));
+        {
+            let mut top = heap.peek_mut().unwrap();
+            *top -= 10;
+        }
+        assert_eq!(heap.peek(), Some(&4));
+        assert_eq!(PeekMut::pop(heap.peek_mut().unwrap()), 4);
+        {
+            let mut top = heap.peek_mut().unwrap();
+            *top = 0;
+            assert_eq!(PeekMut::pop(top), 0);
+        }
+        assert_eq!(drain(&mut heap), vec![2, 1, -5]);
+        assert!(heap.peek_mut().is_none());
+    }
+
+    /// Push pseudo-random values into 'heap', popping once for every three
+    /// pushes, and check the pops against a sorted vector.
+    fn check_mixed_workload<const D: usize>(mut heap: DaryHeap<u64, D, MinComparator>) {
+        let mut naive: Vec<u64> = Vec::new();
+        let mut rng = Lcg::new(7);
+        for step in 0..3000 {
+            if step % 4 == 3 {
+                naive.sort_unstable_by(|a, b| b.cmp(a));
+                assert_eq!(heap.pop(), naive.pop());
+            } else {
+                let x = rng.next_u64() >> 7;
+                heap.push(x);
+                naive.push(x);
+            }
+        }
+        naive.sort_unstable();
+        assert_eq!(heap.into_iter_sorted().collect::<Vec<_>>(), naive);
+    }
+
+    #[test]
+    fn it_supports_any_arity() {
+        check_mixed_workload(DaryHeap::<_, 2, _>::new_min());
+        check_mixed_workload(DaryHeap::<_, 3, _>::new_min());
+        check_mixed_workload(DaryHeap::<_, 4, _>::new_min());
+        check_mixed_workload(DaryHeap::<_, 8, _>::new_min());
+
+        let xs = vec![5, 1, 4, 4, 9, 2, 7, 0, 3, 8, 6];
+        let heap: DaryHeap<i32, 4> = xs.iter().cloned().collect();
+        assert_eq!(heap.peek(), Some(&9));
+        let mut sorted = xs;
+        sorted.sort_unstable();
+        assert_eq!(heap.into_sorted_vec(), sorted);
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_rejects_unary_heap() {
+        DaryHeap::<i32, 1>::new();
+    }
 }
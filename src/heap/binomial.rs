@@ -0,0 +1,247 @@
+//! A binomial heap supporting logarithmic time merging.
+
+use super::{Comparator, MaxComparator, MinComparator, MinPriorityQueue};
+use std::cmp::Ordering;
+use std::iter::FromIterator;
+use std::mem;
+
+/// The root of a binomial tree of order 'k', which has 'k' children of orders
+/// '0, 1, ..., k - 1' and is never below any of them.
+struct Node<T> {
+    value: T,
+    children: Vec<Node<T>>,
+}
+
+/// A forest of binomial trees of distinct orders ordered by the comparator
+/// 'C', a max-heap by default. Merging two heaps adds their forests like
+/// binary numbers.
+pub struct BinomialHeap<T, C = MaxComparator> {
+    /// 'trees[k]' is the tree of order 'k', if there is one.
+    trees: Vec<Option<Node<T>>>,
+    len: usize,
+    cmp: C,
+}
+
+impl<T, C: Comparator<T> + Default> Default for BinomialHeap<T, C> {
+    fn default() -> Self {
+        BinomialHeap::with_comparator(C::default())
+    }
+}
+
+impl<T: Ord> BinomialHeap<T> {
+    /// Create an empty max-heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn new() -> Self {
+        BinomialHeap::with_comparator(MaxComparator)
+    }
+}
+
+impl<T: Ord> BinomialHeap<T, MinComparator> {
+    /// Create an empty min-heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn new_min() -> Self {
+        BinomialHeap::with_comparator(MinComparator)
+    }
+}
+
+impl<T, C: Comparator<T>> BinomialHeap<T, C> {
+    /// Create an empty heap ordered by 'cmp'.
+    ///
+    /// Time complexity: O(1)
+    pub fn with_comparator(cmp: C) -> Self {
+        BinomialHeap {
+            trees: Vec::new(),
+            len: 0,
+            cmp,
+        }
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// True if the heap is empty, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Link two trees of equal order 'k' into a tree of order 'k + 1'.
+    fn link(&self, mut a: Node<T>, mut b: Node<T>) -> Node<T> {
+        if self.cmp.compare(&b.value, &a.value) == Ordering::Greater {
+            mem::swap(&mut a, &mut b);
+        }
+        a.children.push(b);
+        a
+    }
+
+    /// Add the trees 'trees', where 'trees[k]' has order 'k', to the forest.
+    fn meld<I: IntoIterator<Item = Option<Node<T>>>>(&mut self, trees: I) {
+        let mut carry = None;
+        let mut k = 0;
+        for tree in trees {
+            if k == self.trees.len() {
+                self.trees.push(None);
+            }
+            carry = self.add(k, tree, carry);
+            k += 1;
+        }
+        while let Some(c) = carry.take() {
+            if k == self.trees.len() {
+                self.trees.push(None);
+            }
+            carry = self.add(k, Some(c), None);
+            k += 1;
+        }
+    }
+
+    /// Add the trees 'a' and 'b' of order 'k' to 'trees[k]'. Returns the
+    /// resulting tree of order 'k + 1', if any.
+    fn add(&mut self, k: usize, a: Option<Node<T>>, b: Option<Node<T>>) -> Option<Node<T>> {
+        let mut present: Vec<Node<T>> =
+            self.trees[k].take().into_iter().chain(a).chain(b).collect();
+        if present.len() >= 2 {
+            let y = present.pop().unwrap();
+            let x = present.pop().unwrap();
+            self.trees[k] = present.pop();
+            Some(self.link(x, y))
+        } else {
+            self.trees[k] = present.pop();
+            None
+        }
+    }
+
+    /// The order of the tree whose root is the greatest element.
+    fn top(&self) -> Option<usize> {
+        let mut best: Option<usize> = None;
+        for (k, tree) in self.trees.iter().enumerate() {
+            if let Some(node) = tree {
+                let better = match best {
+                    Some(b) => {
+                        let top = &self.trees[b].as_ref().unwrap().value;
+                        self.cmp.compare(&node.value, top) == Ordering::Greater
+                    }
+                    None => true,
+                };
+                if better {
+                    best = Some(k);
+                }
+            }
+        }
+        best
+    }
+
+    /// Insert an element into the heap.
+    ///
+    /// Time complexity: O(1) amortized
+    pub fn push(&mut self, x: T) {
+        self.meld(Some(Some(Node {
+            value: x,
+            children: Vec::new(),
+        })));
+        self.len += 1;
+    }
+
+    /// Get a reference to the greatest element in the heap according to the
+    /// comparator or 'None' if heap is empty.
+    ///
+    /// Time complexity: O(log n)
+    pub fn peek(&self) -> Option<&T> {
+        self.top().map(|k| &self.trees[k].as_ref().unwrap().value)
+    }
+
+    /// Remove the greatest element in the heap according to the comparator.
+    /// Returns the removed element or 'None' in case the heap was empty.
+    ///
+    /// Time complexity: O(log n)
+    pub fn pop(&mut self) -> Option<T> {
+        let k = self.top()?;
+        let Node { value, children } = self.trees[k].take().unwrap();
+        while let Some(None) = self.trees.last() {
+            self.trees.pop();
+        }
+        self.meld(children.into_iter().map(Some));
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Move all elements of 'other' into this heap.
+    ///
+    /// Time complexity: O(log n)
+    pub fn merge(&mut self, mut other: Self) {
+        let trees = mem::take(&mut other.trees);
+        self.meld(trees);
+        self.len += mem::replace(&mut other.len, 0);
+    }
+}
+
+impl<K: Ord, V: Ord> MinPriorityQueue<K, V> for BinomialHeap<(K, V), MinComparator> {
+    fn insert(&mut self, key: K, value: V) {
+        self.push((key, value));
+    }
+
+    fn extract_min(&mut self) -> Option<(K, V)> {
+        self.pop()
+    }
+}
+
+impl<T, C: Comparator<T> + Default> FromIterator<T> for BinomialHeap<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut heap = BinomialHeap::default();
+        heap.extend(iter);
+        heap
+    }
+}
+
+impl<T, C: Comparator<T>> Extend<T> for BinomialHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BinomialHeap;
+    use crate::heap::MinComparator;
+
+    #[test]
+    fn it_pops_in_order() {
+        let xs = [5, 1, 4, 4, 9, 2, 7, 0, 3];
+        let mut heap: BinomialHeap<i32> = xs.iter().cloned().collect();
+        assert_eq!(heap.len(), xs.len());
+        assert_eq!(heap.peek(), Some(&9));
+        let mut popped = Vec::new();
+        while let Some(x) = heap.pop() {
+            popped.push(x);
+        }
+        assert_eq!(popped, vec![9, 7, 5, 4, 4, 3, 2, 1, 0]);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn it_merges() {
+        let mut a: BinomialHeap<i32, MinComparator> = (0..37).map(|i| i * 2).collect();
+        let b: BinomialHeap<i32, MinComparator> = (0..63).map(|i| i * 2 + 1).collect();
+        a.merge(b);
+        a.merge(BinomialHeap::new_min());
+        assert_eq!(a.len(), 100);
+        let mut popped = Vec::new();
+        while let Some(x) = a.pop() {
+            popped.push(x);
+        }
+        let mut expected: Vec<i32> = (0..37)
+            .map(|i| i * 2)
+            .chain((0..63).map(|i| i * 2 + 1))
+            .collect();
+        expected.sort_unstable();
+        assert_eq!(popped, expected);
+    }
+}
@@ -0,0 +1,650 @@
+//! A Fibonacci heap with constant amortized time key changes towards the
+//! root.
+
+use super::{AddressablePriorityQueue, Comparator, MaxComparator, MinComparator, MinPriorityQueue};
+use std::cmp::Ordering;
+use std::mem;
+
+/// A handle to an element pushed into a 'FibonacciHeap'. Vertices are reused
+/// once their element has been removed, and the generation tells a handle to
+/// the old element apart from one to the new.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+    node: usize,
+    generation: usize,
+}
+
+/// A vertex of a Fibonacci heap. Siblings form a circular doubly linked list
+/// through 'left' and 'right'.
+struct Node<T> {
+    /// The element of the vertex, or 'None' if the vertex is free.
+    value: Option<T>,
+    /// The number of times the vertex has been freed.
+    generation: usize,
+    parent: Option<usize>,
+    /// Any one of the children of the vertex.
+    child: Option<usize>,
+    left: usize,
+    right: usize,
+    /// The number of children of the vertex.
+    degree: usize,
+    /// True if the vertex has lost a child since it became a child itself.
+    marked: bool,
+}
+
+/// A forest of heap-ordered trees ordered by the comparator 'C', a max-heap by
+/// default. Elements are accessed through handles like in 'IndexedHeap'.
+pub struct FibonacciHeap<T, C = MaxComparator> {
+    /// Every vertex allocated, including the free ones.
+    nodes: Vec<Node<T>>,
+    /// Indices of the vertices whose element has been removed.
+    free: Vec<usize>,
+    /// The root holding the greatest element.
+    top: Option<usize>,
+    len: usize,
+    cmp: C,
+}
+
+impl<T, C: Comparator<T> + Default> Default for FibonacciHeap<T, C> {
+    fn default() -> Self {
+        FibonacciHeap::with_comparator(C::default())
+    }
+}
+
+impl<T: Ord> FibonacciHeap<T> {
+    /// Create an empty max-heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn new() -> Self {
+        FibonacciHeap::with_comparator(MaxComparator)
+    }
+}
+
+impl<T: Ord> FibonacciHeap<T, MinComparator> {
+    /// Create an empty min-heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn new_min() -> Self {
+        FibonacciHeap::with_comparator(MinComparator)
+    }
+}
+
+impl<T, C: Comparator<T>> FibonacciHeap<T, C> {
+    /// Create an empty heap ordered by 'cmp'.
+    ///
+    /// Time complexity: O(1)
+    pub fn with_comparator(cmp: C) -> Self {
+        FibonacciHeap {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            top: None,
+            len: 0,
+            cmp,
+        }
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// True if the heap is empty, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The element of vertex 'x'.
+    fn value(&self, x: usize) -> &T {
+        self.nodes[x].value.as_ref().unwrap()
+    }
+
+    /// The handle to the element of vertex 'x'.
+    fn handle(&self, x: usize) -> Handle {
+        Handle {
+            node: x,
+            generation: self.nodes[x].generation,
+        }
+    }
+
+    /// The vertex of 'handle' or 'None' if its element is not in the heap.
+    fn find(&self, handle: Handle) -> Option<usize> {
+        let node = self.nodes.get(handle.node)?;
+        if node.generation == handle.generation && node.value.is_some() {
+            Some(handle.node)
+        } else {
+            None
+        }
+    }
+
+    /// True if the element of vertex 'a' should be above that of vertex 'b'.
+    fn above(&self, a: usize, b: usize) -> bool {
+        self.cmp.compare(self.value(a), self.value(b)) == Ordering::Greater
+    }
+
+    /// Remove vertex 'x' from its list of siblings, leaving it in a list of
+    /// its own.
+    fn unlink(&mut self, x: usize) {
+        let (l, r) = (self.nodes[x].left, self.nodes[x].right);
+        self.nodes[l].right = r;
+        self.nodes[r].left = l;
+        self.nodes[x].left = x;
+        self.nodes[x].right = x;
+    }
+
+    /// Insert the single vertex 'x' to the right of vertex 'y' in the list of
+    /// siblings of 'y'.
+    fn splice(&mut self, x: usize, y: usize) {
+        let r = self.nodes[y].right;
+        self.nodes[x].left = y;
+        self.nodes[x].right = r;
+        self.nodes[y].right = x;
+        self.nodes[r].left = x;
+    }
+
+    /// Add the single vertex 'x' to the list of roots.
+    fn add_root(&mut self, x: usize) {
+        self.nodes[x].parent = None;
+        match self.top {
+            None => {
+                self.nodes[x].left = x;
+                self.nodes[x].right = x;
+                self.top = Some(x);
+            }
+            Some(t) => {
+                self.splice(x, t);
+                if self.above(x, t) {
+                    self.top = Some(x);
+                }
+            }
+        }
+    }
+
+    /// Move vertex 'x' from the children of its parent to the list of roots.
+    fn cut(&mut self, x: usize) {
+        let p = self.nodes[x].parent.unwrap();
+        if self.nodes[p].child == Some(x) {
+            let r = self.nodes[x].right;
+            self.nodes[p].child = if r == x { None } else { Some(r) };
+        }
+        self.unlink(x);
+        self.nodes[p].degree -= 1;
+        self.nodes[x].marked = false;
+        self.add_root(x);
+    }
+
+    /// Cut the marked ancestors of a vertex which just lost its child and
+    /// used to have parent 'p', then mark the first unmarked one.
+    fn cascading_cut(&mut self, mut p: usize) {
+        while let Some(pp) = self.nodes[p].parent {
+            if !self.nodes[p].marked {
+                self.nodes[p].marked = true;
+                return;
+            }
+            self.cut(p);
+            p = pp;
+        }
+    }
+
+    /// Make root 'y' a child of root 'x'. Both must be detached from the list
+    /// of roots.
+    fn link(&mut self, y: usize, x: usize) {
+        self.nodes[y].parent = Some(x);
+        self.nodes[y].marked = false;
+        match self.nodes[x].child {
+            None => self.nodes[x].child = Some(y),
+            Some(c) => self.splice(y, c),
+        }
+        self.nodes[x].degree += 1;
+    }
+
+    /// The vertices in the list of siblings containing 'x'.
+    fn siblings(&self, x: usize) -> Vec<usize> {
+        let mut xs = vec![x];
+        let mut y = self.nodes[x].right;
+        while y != x {
+            xs.push(y);
+            y = self.nodes[y].right;
+        }
+        xs
+    }
+
+    /// Link the roots until no two have the same degree.
+    fn consolidate(&mut self) {
+        let roots = match self.top.take() {
+            None => return,
+            Some(t) => self.siblings(t),
+        };
+        let mut by_degree: Vec<Option<usize>> = Vec::new();
+        for mut x in roots {
+            self.unlink(x);
+            let mut d = self.nodes[x].degree;
+            loop {
+                if d >= by_degree.len() {
+                    by_degree.resize(d + 1, None);
+                }
+                let mut y = match by_degree[d].take() {
+                    None => break,
+                    Some(y) => y,
+                };
+                if self.above(y, x) {
+                    mem::swap(&mut x, &mut y);
+                }
+                self.link(y, x);
+                d += 1;
+            }
+            by_degree[d] = Some(x);
+        }
+        for x in by_degree.into_iter().flatten() {
+            self.add_root(x);
+        }
+    }
+
+    /// Detach the greatest root from the heap, moving its children to the list
+    /// of roots. Returns the detached vertex, which keeps its element.
+    fn detach_top(&mut self) -> Option<usize> {
+        let z = self.top?;
+        if let Some(c) = self.nodes[z].child.take() {
+            for x in self.siblings(c) {
+                self.unlink(x);
+                self.nodes[x].parent = None;
+                self.splice(x, z);
+            }
+        }
+        let r = self.nodes[z].right;
+        self.unlink(z);
+        self.top = if r == z { None } else { Some(r) };
+        self.consolidate();
+        self.nodes[z].degree = 0;
+        self.len -= 1;
+        Some(z)
+    }
+
+    /// Make vertex 'x' the greatest root, as if its element had been moved
+    /// above every other.
+    fn move_to_top(&mut self, x: usize) {
+        if let Some(p) = self.nodes[x].parent {
+            self.cut(x);
+            self.cascading_cut(p);
+        }
+        self.top = Some(x);
+    }
+
+    /// Take the element out of the detached vertex 'x' and free the vertex.
+    fn release(&mut self, x: usize) -> T {
+        let node = &mut self.nodes[x];
+        let value = node.value.take().unwrap();
+        node.generation += 1;
+        self.free.push(x);
+        value
+    }
+
+    /// Insert an element into the heap. Returns the handle of the element.
+    ///
+    /// Time complexity: O(1)
+    pub fn push(&mut self, x: T) -> Handle {
+        let h = match self.free.pop() {
+            Some(h) => h,
+            None => {
+                let h = self.nodes.len();
+                self.nodes.push(Node {
+                    value: None,
+                    generation: 0,
+                    parent: None,
+                    child: None,
+                    left: h,
+                    right: h,
+                    degree: 0,
+                    marked: false,
+                });
+                h
+            }
+        };
+        let node = &mut self.nodes[h];
+        node.value = Some(x);
+        node.child = None;
+        node.marked = false;
+        self.add_root(h);
+        self.len += 1;
+        self.handle(h)
+    }
+
+    /// True if the element of 'handle' is still in the heap, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.find(handle).is_some()
+    }
+
+    /// Get a reference to the element of 'handle' or 'None' if it is not in
+    /// the heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.find(handle).map(|x| self.value(x))
+    }
+
+    /// Get the greatest element in the heap according to the comparator along
+    /// with its handle, or 'None' if the heap is empty.
+    ///
+    /// Time complexity: O(1)
+    pub fn peek(&self) -> Option<(Handle, &T)> {
+        self.top.map(|t| (self.handle(t), self.value(t)))
+    }
+
+    /// Remove the greatest element in the heap according to the comparator.
+    /// Returns the removed element and its handle, or 'None' in case the heap
+    /// was empty.
+    ///
+    /// Time complexity: O(log n) amortized
+    pub fn pop(&mut self) -> Option<(Handle, T)> {
+        let z = self.detach_top()?;
+        let handle = self.handle(z);
+        Some((handle, self.release(z)))
+    }
+
+    /// Remove the element of 'handle' from the heap. Returns the removed
+    /// element or 'None' if it was not in the heap.
+    ///
+    /// Time complexity: O(log n) amortized
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let x = self.find(handle)?;
+        self.move_to_top(x);
+        self.detach_top();
+        Some(self.release(x))
+    }
+
+    /// Set the element of vertex 'h' to 'x', which is not below its current
+    /// element, cutting the vertex from its parent if it is now above it.
+    fn raise(&mut self, h: usize, x: T) {
+        self.nodes[h].value = Some(x);
+        match self.nodes[h].parent {
+            Some(p) if self.above(h, p) => {
+                self.cut(h);
+                self.cascading_cut(p);
+            }
+            Some(_) => {}
+            None => {
+                if self.above(h, self.top.unwrap()) {
+                    self.top = Some(h);
+                }
+            }
+        }
+    }
+
+    /// Set the element of vertex 'h' to 'x', which is below its current
+    /// element, by detaching the vertex and inserting it again.
+    fn lower(&mut self, h: usize, x: T) {
+        self.move_to_top(h);
+        self.detach_top();
+        self.nodes[h].value = Some(x);
+        self.nodes[h].marked = false;
+        self.add_root(h);
+        self.len += 1;
+    }
+
+    /// Replace the element of 'handle' with 'x'. If 'x' is not below the
+    /// current element the vertex is cut from its parent, otherwise it is
+    /// detached and inserted again under the same handle.
+    ///
+    /// Panics if the element of 'handle' is not in the heap.
+    ///
+    /// Time complexity: O(1) amortized if 'x' is not below the current
+    /// element, O(log n) amortized otherwise
+    pub fn update(&mut self, handle: Handle, x: T) {
+        let h = self.find(handle).expect("handle not in heap");
+        if self.cmp.compare(&x, self.value(h)) == Ordering::Less {
+            self.lower(h, x);
+        } else {
+            self.raise(h, x);
+        }
+    }
+
+    /// Replace the element of 'handle' with 'x', which must not be below the
+    /// current element according to the comparator, so the element only
+    /// moves towards the root. In a min-heap 'x' is at most the current
+    /// element.
+    ///
+    /// Panics if the element of 'handle' is not in the heap.
+    ///
+    /// Time complexity: O(1) amortized
+    pub fn decrease_key(&mut self, handle: Handle, x: T) {
+        let h = self.find(handle).expect("handle not in heap");
+        debug_assert!(
+            self.cmp.compare(&x, self.value(h)) != Ordering::Less,
+            "new element is below the current one"
+        );
+        self.raise(h, x);
+    }
+
+    /// Replace the element of 'handle' with 'x', which must not be above the
+    /// current element according to the comparator, so the element only
+    /// moves away from the root. In a min-heap 'x' is at least the current
+    /// element.
+    ///
+    /// Panics if the element of 'handle' is not in the heap.
+    ///
+    /// Time complexity: O(log n) amortized
+    pub fn increase_key(&mut self, handle: Handle, x: T) {
+        let h = self.find(handle).expect("handle not in heap");
+        debug_assert!(
+            self.cmp.compare(&x, self.value(h)) != Ordering::Greater,
+            "new element is above the current one"
+        );
+        self.lower(h, x);
+    }
+
+    /// Move all elements of 'other' into this heap. The handles of 'other' are
+    /// no longer valid, and the returned function maps them to handles of
+    /// this heap.
+    ///
+    /// Time complexity: O(1) amortized, plus moving the 'm' vertices of
+    /// 'other' in O(m), where 'm' is at most the largest number of elements
+    /// 'other' ever held
+    pub fn merge(&mut self, other: Self) -> impl Fn(Handle) -> Handle {
+        let offset = self.nodes.len();
+        let shift = |i: usize| i + offset;
+        self.nodes.extend(other.nodes.into_iter().map(|n| Node {
+            parent: n.parent.map(shift),
+            child: n.child.map(shift),
+            left: shift(n.left),
+            right: shift(n.right),
+            ..n
+        }));
+        self.free.extend(other.free.into_iter().map(shift));
+        if let Some(t) = other.top.map(shift) {
+            match self.top {
+                None => self.top = Some(t),
+                Some(s) => {
+                    // Join the two circular lists of roots.
+                    let (s_right, t_left) = (self.nodes[s].right, self.nodes[t].left);
+                    self.nodes[s].right = t;
+                    self.nodes[t].left = s;
+                    self.nodes[t_left].right = s_right;
+                    self.nodes[s_right].left = t_left;
+                    if self.above(t, s) {
+                        self.top = Some(t);
+                    }
+                }
+            }
+        }
+        self.len += other.len;
+        move |h| Handle {
+            node: h.node + offset,
+            generation: h.generation,
+        }
+    }
+}
+
+impl<K: Ord, V: Ord> MinPriorityQueue<K, V> for FibonacciHeap<(K, V), MinComparator> {
+    fn insert(&mut self, key: K, value: V) {
+        self.push((key, value));
+    }
+
+    fn extract_min(&mut self) -> Option<(K, V)> {
+        self.pop().map(|(_, x)| x)
+    }
+}
+
+impl<K: Ord, V: Ord + Clone> AddressablePriorityQueue<K, V>
+    for FibonacciHeap<(K, V), MinComparator>
+{
+    type Handle = Handle;
+
+    fn insert_with_handle(&mut self, key: K, value: V) -> Handle {
+        self.push((key, value))
+    }
+
+    fn decrease_key(&mut self, handle: Handle, key: K) {
+        let value = self.get(handle).expect("handle not in heap").1.clone();
+        FibonacciHeap::decrease_key(self, handle, (key, value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FibonacciHeap;
+    use crate::test_util::Lcg;
+
+    #[test]
+    fn it_pops_in_order() {
+        let mut heap = FibonacciHeap::new();
+        for &x in [5, 1, 4, 4, 9, 2, 7, 0, 3].iter() {
+            heap.push(x);
+        }
+        assert_eq!(heap.peek().map(|(_, &x)| x), Some(9));
+        let mut popped = Vec::new();
+        while let Some((h, x)) = heap.pop() {
+            assert!(!heap.contains(h));
+            popped.push(x);
+        }
+        assert_eq!(popped, vec![9, 7, 5, 4, 4, 3, 2, 1, 0]);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn it_updates_and_removes_by_handle() {
+        let mut heap = FibonacciHeap::new_min();
+        let handles: Vec<_> = (0..20).map(|i| heap.push(i * 10)).collect();
+        assert_eq!(heap.pop(), Some((handles[0], 0)));
+
+        heap.decrease_key(handles[17], -5);
+        assert_eq!(heap.peek(), Some((handles[17], &-5)));
+        heap.increase_key(handles[17], 195);
+        heap.update(handles[1], 155);
+        assert_eq!(heap.peek(), Some((handles[2], &20)));
+
+        assert_eq!(heap.remove(handles[2]), Some(20));
+        assert_eq!(heap.remove(handles[2]), None);
+        assert_eq!(heap.get(handles[1]), Some(&155));
+        assert_eq!(heap.len(), 18);
+
+        let mut popped = Vec::new();
+        while let Some((_, x)) = heap.pop() {
+            popped.push(x);
+        }
+        let mut expected: Vec<i32> = (3..20).filter(|&i| i != 17).map(|i| i * 10).collect();
+        expected.extend(vec![155, 195]);
+        expected.sort_unstable();
+        assert_eq!(popped, expected);
+    }
+
+    #[test]
+    fn it_matches_naive_priority_queue() {
+        // Pseudo-random operations checked against a plain vector.
+        let mut heap = FibonacciHeap::new_min();
+        let mut naive: Vec<(usize, i64)> = Vec::new();
+        let mut handles = Vec::new();
+        let mut rng = Lcg::new(12345);
+        for step in 0..3000 {
+            let r = rng.next_u64() as i64;
+            match r % 5 {
+                0 | 1 => {
+                    handles.push(heap.push(r % 1000));
+                    naive.push((handles.len() - 1, r % 1000));
+                }
+                2 if !naive.is_empty() => {
+                    let k = (r as usize / 5) % naive.len();
+                    let (id, _) = naive[k];
+                    naive[k].1 = r % 997 - step;
+                    heap.update(handles[id], naive[k].1);
+                }
+                3 if !naive.is_empty() => {
+                    let k = (r as usize / 5) % naive.len();
+                    let (id, x) = naive.swap_remove(k);
+                    assert_eq!(heap.remove(handles[id]), Some(x));
+                }
+                _ if !naive.is_empty() => {
+                    let k = (0..naive.len()).min_by_key(|&k| naive[k].1).unwrap();
+                    let (_, x) = naive.swap_remove(k);
+                    assert_eq!(heap.pop().map(|(_, y)| y), Some(x));
+                }
+                _ => {}
+            }
+            let min = naive.iter().map(|&(_, x)| x).min();
+            assert_eq!(heap.peek().map(|(_, &x)| x), min);
+            assert_eq!(heap.len(), naive.len());
+        }
+    }
+
+    #[test]
+    fn it_merges() {
+        let mut a = FibonacciHeap::new_min();
+        let mut b = FibonacciHeap::new_min();
+        for i in 0..50 {
+            a.push(i * 2);
+            b.push(i * 2 + 1);
+        }
+        a.pop();
+        let hb = b.push(-3);
+        b.pop();
+        let hb2 = b.push(500);
+        let translate = a.merge(b);
+        assert!(!a.contains(translate(hb)));
+        a.decrease_key(translate(hb2), -1);
+        assert_eq!(a.len(), 100);
+        assert_eq!(a.pop(), Some((translate(hb2), -1)));
+        for i in 1..100 {
+            assert_eq!(a.pop().map(|(_, x)| x), Some(i));
+        }
+        assert_eq!(a.pop(), None);
+    }
+
+    #[test]
+    fn it_merges_repeatedly() {
+        let mut a = FibonacciHeap::new();
+        let mut b = FibonacciHeap::new();
+        let mut c = FibonacciHeap::new();
+        let ha = a.push(1);
+        let hb = b.push(2);
+        let hc = c.push(3);
+        let c_to_b = b.merge(c);
+        let b_to_a = a.merge(b);
+        let (hb, hc) = (b_to_a(hb), b_to_a(c_to_b(hc)));
+        assert_eq!(a.len(), 3);
+        assert_eq!(a.get(ha), Some(&1));
+        assert_eq!(a.get(hb), Some(&2));
+        assert_eq!(a.remove(hc), Some(3));
+        let hd = a.push(4);
+        assert_eq!(a.peek(), Some((hd, &4)));
+        assert_eq!(a.pop(), Some((hd, 4)));
+        assert_eq!(a.pop(), Some((hb, 2)));
+        assert_eq!(a.pop(), Some((ha, 1)));
+    }
+
+    #[test]
+    fn it_reuses_vertices() {
+        let mut heap = FibonacciHeap::new_min();
+        let old = heap.push(1);
+        assert_eq!(heap.pop(), Some((old, 1)));
+        let new = heap.push(2);
+        assert_ne!(old, new);
+        assert!(!heap.contains(old));
+        assert_eq!(heap.remove(old), None);
+        assert_eq!(heap.get(new), Some(&2));
+        assert_eq!(heap.nodes.len(), 1);
+    }
+}
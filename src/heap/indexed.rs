@@ -0,0 +1,383 @@
+//! A binary heap where pushed elements can be looked up, changed and removed
+//! through handles.
+
+use super::{sift_down_slots, sift_up_slots, HeapSlots};
+use super::{AddressablePriorityQueue, Comparator, MaxComparator, MinComparator, MinPriorityQueue};
+use std::cmp::Ordering;
+
+/// A handle to an element pushed into an 'IndexedHeap'. Slots are reused once
+/// their element has been removed, and the generation tells a handle to the
+/// old element apart from one to the new, so a handle to a removed element
+/// stays invalid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+    slot: usize,
+    generation: usize,
+}
+
+/// The storage of one element.
+struct Slot<T> {
+    /// The element, or 'None' if the slot is free.
+    value: Option<T>,
+    /// The index of the slot in the heap while it holds an element.
+    position: usize,
+    /// The number of times the slot has been freed.
+    generation: usize,
+}
+
+/// A binary heap ordered by the comparator 'C' which tracks the position of
+/// each element, allowing changes and removals in the middle of the heap.
+pub struct IndexedHeap<T, C = MaxComparator> {
+    /// The heap of slots, ordered by their elements.
+    heap: Vec<usize>,
+    /// The slot of each element pushed, reused after the element is removed.
+    slots: Vec<Slot<T>>,
+    /// Indices of the free slots.
+    free: Vec<usize>,
+    cmp: C,
+}
+
+impl<T, C: Comparator<T> + Default> Default for IndexedHeap<T, C> {
+    fn default() -> Self {
+        IndexedHeap::with_comparator(C::default())
+    }
+}
+
+impl<T: Ord> IndexedHeap<T> {
+    /// Create an empty indexed max-heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn new() -> Self {
+        IndexedHeap::with_comparator(MaxComparator)
+    }
+}
+
+impl<T: Ord> IndexedHeap<T, MinComparator> {
+    /// Create an empty indexed min-heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn new_min() -> Self {
+        IndexedHeap::with_comparator(MinComparator)
+    }
+}
+
+impl<T, C: Comparator<T>> HeapSlots for IndexedHeap<T, C> {
+    fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    fn above(&self, i: usize, j: usize) -> bool {
+        self.cmp.compare(self.value(i), self.value(j)) == Ordering::Greater
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        self.slots[self.heap[i]].position = i;
+        self.slots[self.heap[j]].position = j;
+    }
+}
+
+impl<T, C: Comparator<T>> IndexedHeap<T, C> {
+    /// Create an empty indexed heap ordered by 'cmp'.
+    ///
+    /// Time complexity: O(1)
+    pub fn with_comparator(cmp: C) -> Self {
+        IndexedHeap {
+            heap: Vec::new(),
+            slots: Vec::new(),
+            free: Vec::new(),
+            cmp,
+        }
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// True if the heap is empty, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// The element at index 'i' of the heap.
+    fn value(&self, i: usize) -> &T {
+        self.slots[self.heap[i]].value.as_ref().unwrap()
+    }
+
+    /// The handle to the element in slot 's'.
+    fn handle(&self, s: usize) -> Handle {
+        Handle {
+            slot: s,
+            generation: self.slots[s].generation,
+        }
+    }
+
+    /// The slot of 'handle' or 'None' if its element is not in the heap.
+    fn find(&self, handle: Handle) -> Option<usize> {
+        let slot = self.slots.get(handle.slot)?;
+        if slot.generation == handle.generation && slot.value.is_some() {
+            Some(handle.slot)
+        } else {
+            None
+        }
+    }
+
+    /// Restore the heap order around index 'i' after its element changed.
+    fn fix(&mut self, i: usize) {
+        if sift_up_slots::<2, _>(self, i) == i {
+            sift_down_slots::<2, _>(self, i);
+        }
+    }
+
+    /// Insert an element into the heap. Returns the handle of the element.
+    ///
+    /// Time complexity: O(log n)
+    pub fn push(&mut self, x: T) -> Handle {
+        let position = self.heap.len();
+        let s = match self.free.pop() {
+            Some(s) => {
+                self.slots[s].value = Some(x);
+                self.slots[s].position = position;
+                s
+            }
+            None => {
+                self.slots.push(Slot {
+                    value: Some(x),
+                    position,
+                    generation: 0,
+                });
+                self.slots.len() - 1
+            }
+        };
+        self.heap.push(s);
+        sift_up_slots::<2, _>(self, position);
+        self.handle(s)
+    }
+
+    /// True if the element of 'handle' is still in the heap, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.find(handle).is_some()
+    }
+
+    /// Get a reference to the element of 'handle' or 'None' if it is not in
+    /// the heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.find(handle).and_then(|s| self.slots[s].value.as_ref())
+    }
+
+    /// Get the greatest element in the heap according to the comparator along
+    /// with its handle, or 'None' if the heap is empty.
+    ///
+    /// Time complexity: O(1)
+    pub fn peek(&self) -> Option<(Handle, &T)> {
+        self.heap.first().map(|&s| (self.handle(s), self.value(0)))
+    }
+
+    /// Remove the greatest element in the heap according to the comparator.
+    /// Returns the removed element and its handle, or 'None' in case the heap
+    /// was empty.
+    ///
+    /// Time complexity: O(log n)
+    pub fn pop(&mut self) -> Option<(Handle, T)> {
+        let h = self.handle(*self.heap.first()?);
+        self.remove(h).map(|x| (h, x))
+    }
+
+    /// Remove the element of 'handle' from the heap. Returns the removed
+    /// element or 'None' if it was not in the heap.
+    ///
+    /// Time complexity: O(log n)
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let s = self.find(handle)?;
+        let i = self.slots[s].position;
+        let last = self.heap.len() - 1;
+        HeapSlots::swap(self, i, last);
+        self.heap.pop();
+        if i < self.heap.len() {
+            self.fix(i);
+        }
+        let slot = &mut self.slots[s];
+        slot.generation += 1;
+        self.free.push(s);
+        slot.value.take()
+    }
+
+    /// Replace the element of 'handle' with 'x'. The element may move in
+    /// either direction.
+    ///
+    /// Panics if the element of 'handle' is not in the heap.
+    ///
+    /// Time complexity: O(log n)
+    pub fn update(&mut self, handle: Handle, x: T) {
+        let s = self.find(handle).expect("handle not in heap");
+        self.slots[s].value = Some(x);
+        self.fix(self.slots[s].position);
+    }
+
+    /// Replace the element of 'handle' with 'x', which must not be below the
+    /// current element according to the comparator, so the element only
+    /// moves towards the root. In a min-heap 'x' is at most the current
+    /// element.
+    ///
+    /// Panics if the element of 'handle' is not in the heap.
+    ///
+    /// Time complexity: O(log n)
+    pub fn decrease_key(&mut self, handle: Handle, x: T) {
+        let s = self.find(handle).expect("handle not in heap");
+        debug_assert!(
+            self.cmp.compare(&x, self.value(self.slots[s].position)) != Ordering::Less,
+            "new element is below the current one"
+        );
+        self.slots[s].value = Some(x);
+        sift_up_slots::<2, _>(self, self.slots[s].position);
+    }
+
+    /// Replace the element of 'handle' with 'x', which must not be above the
+    /// current element according to the comparator, so the element only
+    /// moves away from the root. In a min-heap 'x' is at least the current
+    /// element.
+    ///
+    /// Panics if the element of 'handle' is not in the heap.
+    ///
+    /// Time complexity: O(log n)
+    pub fn increase_key(&mut self, handle: Handle, x: T) {
+        let s = self.find(handle).expect("handle not in heap");
+        debug_assert!(
+            self.cmp.compare(&x, self.value(self.slots[s].position)) != Ordering::Greater,
+            "new element is above the current one"
+        );
+        self.slots[s].value = Some(x);
+        sift_down_slots::<2, _>(self, self.slots[s].position);
+    }
+}
+
+impl<K: Ord, V: Ord> MinPriorityQueue<K, V> for IndexedHeap<(K, V), MinComparator> {
+    fn insert(&mut self, key: K, value: V) {
+        self.push((key, value));
+    }
+
+    fn extract_min(&mut self) -> Option<(K, V)> {
+        self.pop().map(|(_, x)| x)
+    }
+}
+
+impl<K: Ord, V: Ord + Clone> AddressablePriorityQueue<K, V> for IndexedHeap<(K, V), MinComparator> {
+    type Handle = Handle;
+
+    fn insert_with_handle(&mut self, key: K, value: V) -> Handle {
+        self.push((key, value))
+    }
+
+    fn decrease_key(&mut self, handle: Handle, key: K) {
+        let value = self.get(handle).expect("handle not in heap").1.clone();
+        IndexedHeap::decrease_key(self, handle, (key, value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IndexedHeap;
+    use crate::test_util::Lcg;
+
+    #[test]
+    fn it_pops_in_order() {
+        let mut heap = IndexedHeap::new();
+        for &x in [5, 1, 4, 4, 9, 2, 7].iter() {
+            heap.push(x);
+        }
+        let mut popped = Vec::new();
+        while let Some((h, x)) = heap.pop() {
+            assert!(!heap.contains(h));
+            popped.push(x);
+        }
+        assert_eq!(popped, vec![9, 7, 5, 4, 4, 2, 1]);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn it_updates_and_removes_by_handle() {
+        let mut heap = IndexedHeap::new_min();
+        let handles: Vec<_> = (0..10).map(|i| heap.push(i * 10)).collect();
+        assert_eq!(heap.peek(), Some((handles[0], &0)));
+
+        heap.decrease_key(handles[7], -5);
+        assert_eq!(heap.peek(), Some((handles[7], &-5)));
+        heap.increase_key(handles[7], 95);
+        heap.update(handles[0], 55);
+        assert_eq!(heap.peek(), Some((handles[1], &10)));
+
+        heap.increase_key(handles[1], 65);
+        heap.decrease_key(handles[9], 85);
+        assert_eq!(heap.peek(), Some((handles[2], &20)));
+        heap.decrease_key(handles[1], 10);
+
+        assert_eq!(heap.remove(handles[1]), Some(10));
+        assert_eq!(heap.remove(handles[1]), None);
+        assert!(!heap.contains(handles[1]));
+        assert_eq!(heap.get(handles[1]), None);
+        assert_eq!(heap.get(handles[0]), Some(&55));
+        assert_eq!(heap.len(), 9);
+
+        let mut popped = Vec::new();
+        while let Some((_, x)) = heap.pop() {
+            popped.push(x);
+        }
+        assert_eq!(popped, vec![20, 30, 40, 50, 55, 60, 80, 85, 95]);
+    }
+
+    #[test]
+    fn it_reuses_slots() {
+        let mut heap = IndexedHeap::new();
+        let old = heap.push(1);
+        assert_eq!(heap.pop(), Some((old, 1)));
+        let new = heap.push(2);
+        assert_ne!(old, new);
+        assert!(!heap.contains(old));
+        assert_eq!(heap.get(old), None);
+        assert_eq!(heap.remove(old), None);
+        assert_eq!(heap.get(new), Some(&2));
+        assert_eq!(heap.slots.len(), 1);
+    }
+
+    #[test]
+    fn it_matches_naive_priority_queue() {
+        // Pseudo-random operations checked against a plain vector.
+        let mut heap = IndexedHeap::new_min();
+        let mut naive: Vec<(usize, i64)> = Vec::new();
+        let mut handles = Vec::new();
+        let mut rng = Lcg::new(12345);
+        for step in 0..2000 {
+            let r = rng.next_u64() as i64;
+            match r % 4 {
+                0 | 1 => {
+                    handles.push(heap.push(r % 1000));
+                    naive.push((handles.len() - 1, r % 1000));
+                }
+                2 if !naive.is_empty() => {
+                    let k = (r as usize / 4) % naive.len();
+                    let (id, _) = naive[k];
+                    naive[k].1 = r % 997 - step;
+                    heap.update(handles[id], naive[k].1);
+                }
+                _ if !naive.is_empty() => {
+                    let k = (r as usize / 4) % naive.len();
+                    let (id, x) = naive.swap_remove(k);
+                    assert_eq!(heap.remove(handles[id]), Some(x));
+                }
+                _ => {}
+            }
+            let min = naive.iter().map(|&(_, x)| x).min();
+            assert_eq!(heap.peek().map(|(_, &x)| x), min);
+            assert_eq!(heap.len(), naive.len());
+        }
+    }
+}
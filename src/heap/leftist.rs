@@ -0,0 +1,220 @@
+//! A leftist heap supporting logarithmic time merging.
+
+use super::{Comparator, MaxComparator, MinComparator, MinPriorityQueue};
+use std::cmp::Ordering;
+use std::iter::FromIterator;
+use std::mem;
+
+/// A vertex of a leftist heap, never below any of its children.
+struct Node<T> {
+    value: T,
+    /// The length of the rightmost path from the vertex down to a missing
+    /// child.
+    rank: usize,
+    left: Option<Box<Node<T>>>,
+    right: Option<Box<Node<T>>>,
+}
+
+/// The rank of 'node', zero for a missing vertex.
+fn rank<T>(node: &Option<Box<Node<T>>>) -> usize {
+    node.as_ref().map_or(0, |n| n.rank)
+}
+
+/// A heap-ordered binary tree ordered by the comparator 'C', a max-heap by
+/// default. The rank of every left child is at least that of its sibling, so
+/// the rightmost path, along which heaps are merged, has length O(log n).
+pub struct LeftistHeap<T, C = MaxComparator> {
+    root: Option<Box<Node<T>>>,
+    len: usize,
+    cmp: C,
+}
+
+impl<T, C: Comparator<T> + Default> Default for LeftistHeap<T, C> {
+    fn default() -> Self {
+        LeftistHeap::with_comparator(C::default())
+    }
+}
+
+impl<T: Ord> LeftistHeap<T> {
+    /// Create an empty max-heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn new() -> Self {
+        LeftistHeap::with_comparator(MaxComparator)
+    }
+}
+
+impl<T: Ord> LeftistHeap<T, MinComparator> {
+    /// Create an empty min-heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn new_min() -> Self {
+        LeftistHeap::with_comparator(MinComparator)
+    }
+}
+
+impl<T, C: Comparator<T>> LeftistHeap<T, C> {
+    /// Create an empty heap ordered by 'cmp'.
+    ///
+    /// Time complexity: O(1)
+    pub fn with_comparator(cmp: C) -> Self {
+        LeftistHeap {
+            root: None,
+            len: 0,
+            cmp,
+        }
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// True if the heap is empty, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Merge the trees 'a' and 'b' along their rightmost paths.
+    fn meld(&self, a: Option<Box<Node<T>>>, b: Option<Box<Node<T>>>) -> Option<Box<Node<T>>> {
+        match (a, b) {
+            (None, node) | (node, None) => node,
+            (Some(mut a), Some(mut b)) => {
+                if self.cmp.compare(&b.value, &a.value) == Ordering::Greater {
+                    mem::swap(&mut a, &mut b);
+                }
+                a.right = self.meld(a.right.take(), Some(b));
+                if rank(&a.left) < rank(&a.right) {
+                    mem::swap(&mut a.left, &mut a.right);
+                }
+                a.rank = rank(&a.right) + 1;
+                Some(a)
+            }
+        }
+    }
+
+    /// Insert an element into the heap.
+    ///
+    /// Time complexity: O(log n)
+    pub fn push(&mut self, x: T) {
+        let node = Box::new(Node {
+            value: x,
+            rank: 1,
+            left: None,
+            right: None,
+        });
+        let root = self.root.take();
+        self.root = self.meld(root, Some(node));
+        self.len += 1;
+    }
+
+    /// Get a reference to the greatest element in the heap according to the
+    /// comparator or 'None' if heap is empty.
+    ///
+    /// Time complexity: O(1)
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(|node| &node.value)
+    }
+
+    /// Remove the greatest element in the heap according to the comparator.
+    /// Returns the removed element or 'None' in case the heap was empty.
+    ///
+    /// Time complexity: O(log n)
+    pub fn pop(&mut self) -> Option<T> {
+        let mut root = self.root.take()?;
+        self.root = self.meld(root.left.take(), root.right.take());
+        self.len -= 1;
+        Some(root.value)
+    }
+
+    /// Move all elements of 'other' into this heap.
+    ///
+    /// Time complexity: O(log n)
+    pub fn merge(&mut self, mut other: Self) {
+        let root = self.root.take();
+        self.root = self.meld(root, other.root.take());
+        self.len += mem::replace(&mut other.len, 0);
+    }
+}
+
+impl<T, C> Drop for LeftistHeap<T, C> {
+    /// Drop the vertices iteratively since the leftmost path can be as long as
+    /// the number of elements.
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<T>>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.extend(node.left.take());
+            stack.extend(node.right.take());
+        }
+    }
+}
+
+impl<K: Ord, V: Ord> MinPriorityQueue<K, V> for LeftistHeap<(K, V), MinComparator> {
+    fn insert(&mut self, key: K, value: V) {
+        self.push((key, value));
+    }
+
+    fn extract_min(&mut self) -> Option<(K, V)> {
+        self.pop()
+    }
+}
+
+impl<T, C: Comparator<T> + Default> FromIterator<T> for LeftistHeap<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut heap = LeftistHeap::default();
+        heap.extend(iter);
+        heap
+    }
+}
+
+impl<T, C: Comparator<T>> Extend<T> for LeftistHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LeftistHeap;
+    use crate::heap::MinComparator;
+
+    #[test]
+    fn it_pops_in_order() {
+        let xs = [5, 1, 4, 4, 9, 2, 7, 0, 3];
+        let mut heap: LeftistHeap<i32> = xs.iter().cloned().collect();
+        assert_eq!(heap.len(), xs.len());
+        assert_eq!(heap.peek(), Some(&9));
+        let mut popped = Vec::new();
+        while let Some(x) = heap.pop() {
+            popped.push(x);
+        }
+        assert_eq!(popped, vec![9, 7, 5, 4, 4, 3, 2, 1, 0]);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn it_merges() {
+        let mut a: LeftistHeap<i32, MinComparator> = (0..50).map(|i| i * 2).collect();
+        let b: LeftistHeap<i32, MinComparator> = (0..50).map(|i| i * 2 + 1).collect();
+        a.merge(b);
+        a.merge(LeftistHeap::new_min());
+        assert_eq!(a.len(), 100);
+        for i in 0..100 {
+            assert_eq!(a.pop(), Some(i));
+        }
+        assert_eq!(a.pop(), None);
+    }
+
+    #[test]
+    fn it_drops_deep_heap() {
+        let mut heap = LeftistHeap::new();
+        heap.extend(0..1_000_000);
+        assert_eq!(heap.pop(), Some(999_999));
+    }
+}
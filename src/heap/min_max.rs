@@ -0,0 +1,300 @@
+//! A double-ended heap giving access to both its smallest and largest element.
+
+use super::{Comparator, MaxComparator, MinPriorityQueue};
+use std::cmp::Ordering;
+use std::iter::FromIterator;
+
+/// The depth of index 'i' in a perfectly balanced binary tree.
+fn level(i: usize) -> u32 {
+    usize::BITS - 1 - (i + 1).leading_zeros()
+}
+
+/// A binary tree where vertices on even levels are not above any of their
+/// descendants and vertices on odd levels are not below any of their
+/// descendants. The smallest element is at the root and the largest is one of
+/// its children. Elements are ordered by the comparator 'C', which uses their
+/// natural order by default.
+pub struct MinMaxHeap<T, C = MaxComparator> {
+    xs: Vec<T>,
+    cmp: C,
+}
+
+impl<T, C: Comparator<T> + Default> Default for MinMaxHeap<T, C> {
+    fn default() -> Self {
+        MinMaxHeap::with_comparator(C::default())
+    }
+}
+
+impl<T: Ord> MinMaxHeap<T> {
+    /// Create an empty min-max heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn new() -> Self {
+        MinMaxHeap::with_comparator(MaxComparator)
+    }
+}
+
+impl<T, C: Comparator<T>> MinMaxHeap<T, C> {
+    /// Create an empty min-max heap ordered by 'cmp'.
+    ///
+    /// Time complexity: O(1)
+    pub fn with_comparator(cmp: C) -> Self {
+        MinMaxHeap {
+            xs: Vec::new(),
+            cmp,
+        }
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.xs.len()
+    }
+
+    /// True if the heap is empty, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.xs.is_empty()
+    }
+
+    /// Remove all elements from the heap.
+    ///
+    /// Time complexity: O(n)
+    pub fn clear(&mut self) {
+        self.xs.clear();
+    }
+
+    /// True if the element at index 'i' should be closer to the root than the
+    /// element at index 'j' on a max level if 'max', otherwise on a min level.
+    fn before(&self, i: usize, j: usize, max: bool) -> bool {
+        let expected = if max {
+            Ordering::Greater
+        } else {
+            Ordering::Less
+        };
+        self.cmp.compare(&self.xs[i], &self.xs[j]) == expected
+    }
+
+    /// Move the element at index 'i' up through the levels of its own kind.
+    fn bubble_up(&mut self, mut i: usize, max: bool) {
+        while i > 2 {
+            let g = ((i - 1) / 2 - 1) / 2;
+            if !self.before(i, g, max) {
+                break;
+            }
+            self.xs.swap(i, g);
+            i = g;
+        }
+    }
+
+    /// Move the element at index 'i' down until the heap order holds.
+    fn trickle_down(&mut self, mut i: usize) {
+        let max = level(i) % 2 == 1;
+        loop {
+            // The most extreme of the children and grandchildren of 'i'.
+            let n = self.xs.len();
+            let first = 2 * i + 1;
+            let descendants = (first..first + 2)
+                .chain(2 * first + 1..2 * first + 5)
+                .filter(|&j| j < n);
+            let m = match descendants.reduce(|a, b| if self.before(b, a, max) { b } else { a }) {
+                None => return,
+                Some(m) => m,
+            };
+            if !self.before(m, i, max) {
+                return;
+            }
+            self.xs.swap(m, i);
+            if m <= first + 1 {
+                return;
+            }
+            let p = (m - 1) / 2;
+            if self.before(p, m, max) {
+                self.xs.swap(m, p);
+            }
+            i = m;
+        }
+    }
+
+    /// Insert an element into the heap.
+    ///
+    /// Time complexity: O(log n)
+    pub fn push(&mut self, x: T) {
+        self.xs.push(x);
+        let i = self.xs.len() - 1;
+        if i == 0 {
+            return;
+        }
+        let p = (i - 1) / 2;
+        let max = level(i) % 2 == 1;
+        if self.before(p, i, max) {
+            self.xs.swap(i, p);
+            self.bubble_up(p, !max);
+        } else {
+            self.bubble_up(i, max);
+        }
+    }
+
+    /// The index of the largest element, or 'None' if the heap is empty.
+    fn max_index(&self) -> Option<usize> {
+        match self.xs.len() {
+            0 => None,
+            1 => Some(0),
+            2 => Some(1),
+            _ => Some(if self.before(2, 1, true) { 2 } else { 1 }),
+        }
+    }
+
+    /// Get a reference to the smallest element in the heap according to the
+    /// comparator or 'None' if heap is empty.
+    ///
+    /// Time complexity: O(1)
+    pub fn peek_min(&self) -> Option<&T> {
+        self.xs.first()
+    }
+
+    /// Get a reference to the largest element in the heap according to the
+    /// comparator or 'None' if heap is empty.
+    ///
+    /// Time complexity: O(1)
+    pub fn peek_max(&self) -> Option<&T> {
+        self.max_index().map(|i| &self.xs[i])
+    }
+
+    /// Remove the element at index 'i'.
+    fn remove(&mut self, i: usize) -> T {
+        let x = self.xs.swap_remove(i);
+        if i < self.xs.len() {
+            self.trickle_down(i);
+        }
+        x
+    }
+
+    /// Remove the smallest element in the heap according to the comparator.
+    /// Returns the removed element or 'None' in case the heap was empty.
+    ///
+    /// Time complexity: O(log n)
+    pub fn pop_min(&mut self) -> Option<T> {
+        if self.xs.is_empty() {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+
+    /// Remove the largest element in the heap according to the comparator.
+    /// Returns the removed element or 'None' in case the heap was empty.
+    ///
+    /// Time complexity: O(log n)
+    pub fn pop_max(&mut self) -> Option<T> {
+        self.max_index().map(|i| self.remove(i))
+    }
+
+    /// The elements of the heap in arbitrary order.
+    ///
+    /// Time complexity: O(1)
+    pub fn into_vec(self) -> Vec<T> {
+        self.xs
+    }
+}
+
+impl<K: Ord, V: Ord> MinPriorityQueue<K, V> for MinMaxHeap<(K, V)> {
+    fn insert(&mut self, key: K, value: V) {
+        self.push((key, value));
+    }
+
+    fn extract_min(&mut self) -> Option<(K, V)> {
+        self.pop_min()
+    }
+}
+
+impl<T, C: Comparator<T> + Default> FromIterator<T> for MinMaxHeap<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut heap = MinMaxHeap::default();
+        heap.extend(iter);
+        heap
+    }
+}
+
+impl<T, C: Comparator<T>> Extend<T> for MinMaxHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{level, MinMaxHeap};
+    use crate::test_util::Lcg;
+
+    #[test]
+    fn it_computes_levels() {
+        let levels: Vec<u32> = (0..8).map(level).collect();
+        assert_eq!(levels, vec![0, 1, 1, 2, 2, 2, 2, 3]);
+    }
+
+    #[test]
+    fn it_pops_from_both_ends() {
+        let xs = [5, 1, 4, 4, 9, 2, 7, 0, 3, 8, 6];
+        let mut heap: MinMaxHeap<i32> = xs.iter().cloned().collect();
+        assert_eq!(heap.peek_min(), Some(&0));
+        assert_eq!(heap.peek_max(), Some(&9));
+        let mut sorted = xs.to_vec();
+        sorted.sort_unstable();
+        while !sorted.is_empty() {
+            assert_eq!(heap.pop_max(), sorted.pop());
+            if !sorted.is_empty() {
+                assert_eq!(heap.pop_min(), Some(sorted.remove(0)));
+            }
+        }
+        assert_eq!(heap.pop_min(), None);
+        assert_eq!(heap.pop_max(), None);
+    }
+
+    #[test]
+    fn it_matches_sorted_vector() {
+        // Pseudo-random pushes and pops from either end checked against a
+        // sorted vector.
+        let mut heap = MinMaxHeap::new();
+        let mut naive: Vec<u64> = Vec::new();
+        let mut rng = Lcg::new(99);
+        for _ in 0..3000 {
+            match rng.next(3) {
+                0 => {
+                    assert_eq!(heap.pop_min(), naive.first().cloned());
+                    if !naive.is_empty() {
+                        naive.remove(0);
+                    }
+                }
+                1 => assert_eq!(heap.pop_max(), naive.pop()),
+                _ => {
+                    let x = rng.next_u64() % 500;
+                    heap.push(x);
+                    let k = naive.partition_point(|&y| y < x);
+                    naive.insert(k, x);
+                }
+            }
+            assert_eq!(heap.peek_min(), naive.first());
+            assert_eq!(heap.peek_max(), naive.last());
+            assert_eq!(heap.len(), naive.len());
+        }
+    }
+
+    #[test]
+    fn it_keeps_best_k() {
+        let mut best = MinMaxHeap::new();
+        for x in (0..100).map(|i| (i * 37) % 101) {
+            best.push(x);
+            if best.len() > 5 {
+                best.pop_min();
+            }
+        }
+        let mut kept = best.into_vec();
+        kept.sort_unstable();
+        assert_eq!(kept, vec![96, 97, 98, 99, 100]);
+    }
+}
@@ -0,0 +1,224 @@
+//! A pairing heap supporting constant time merging.
+
+use super::{Comparator, MaxComparator, MinComparator, MinPriorityQueue};
+use std::cmp::Ordering;
+use std::iter::FromIterator;
+use std::mem;
+
+/// A vertex of a pairing heap, never below any of its children.
+struct Node<T> {
+    value: T,
+    children: Vec<Node<T>>,
+}
+
+/// A heap-ordered multiway tree ordered by the comparator 'C', a max-heap by
+/// default. Merging two heaps makes one root a child of the other, and popping
+/// merges the children of the root in pairs.
+pub struct PairingHeap<T, C = MaxComparator> {
+    root: Option<Node<T>>,
+    len: usize,
+    cmp: C,
+}
+
+impl<T, C: Comparator<T> + Default> Default for PairingHeap<T, C> {
+    fn default() -> Self {
+        PairingHeap::with_comparator(C::default())
+    }
+}
+
+impl<T: Ord> PairingHeap<T> {
+    /// Create an empty max-heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn new() -> Self {
+        PairingHeap::with_comparator(MaxComparator)
+    }
+}
+
+impl<T: Ord> PairingHeap<T, MinComparator> {
+    /// Create an empty min-heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn new_min() -> Self {
+        PairingHeap::with_comparator(MinComparator)
+    }
+}
+
+impl<T, C: Comparator<T>> PairingHeap<T, C> {
+    /// Create an empty heap ordered by 'cmp'.
+    ///
+    /// Time complexity: O(1)
+    pub fn with_comparator(cmp: C) -> Self {
+        PairingHeap {
+            root: None,
+            len: 0,
+            cmp,
+        }
+    }
+
+    /// The number of elements in the heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// True if the heap is empty, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Make the root which is below the other a child of it.
+    fn link(&self, mut a: Node<T>, mut b: Node<T>) -> Node<T> {
+        if self.cmp.compare(&b.value, &a.value) == Ordering::Greater {
+            b.children.push(a);
+            b
+        } else {
+            a.children.push(b);
+            a
+        }
+    }
+
+    /// Make 'node' the root if the heap is empty, otherwise link it with the
+    /// root.
+    fn meld(&mut self, node: Node<T>) {
+        self.root = Some(match self.root.take() {
+            None => node,
+            Some(root) => self.link(root, node),
+        });
+    }
+
+    /// Insert an element into the heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn push(&mut self, x: T) {
+        self.meld(Node {
+            value: x,
+            children: Vec::new(),
+        });
+        self.len += 1;
+    }
+
+    /// Get a reference to the greatest element in the heap according to the
+    /// comparator or 'None' if heap is empty.
+    ///
+    /// Time complexity: O(1)
+    pub fn peek(&self) -> Option<&T> {
+        self.root.as_ref().map(|node| &node.value)
+    }
+
+    /// Remove the greatest element in the heap according to the comparator.
+    /// Returns the removed element or 'None' in case the heap was empty.
+    ///
+    /// Time complexity: O(log n) amortized
+    pub fn pop(&mut self) -> Option<T> {
+        let Node { value, children } = self.root.take()?;
+        self.len -= 1;
+        // Link the children in pairs from left to right, then link the pairs
+        // from right to left.
+        // 'usize::div_ceil' needs a newer toolchain than the crate supports.
+        #[allow(clippy::manual_div_ceil)]
+        let mut pairs = Vec::with_capacity((children.len() + 1) / 2);
+        let mut children = children.into_iter();
+        while let Some(a) = children.next() {
+            pairs.push(match children.next() {
+                Some(b) => self.link(a, b),
+                None => a,
+            });
+        }
+        self.root = pairs
+            .into_iter()
+            .rev()
+            .reduce(|acc, node| self.link(acc, node));
+        Some(value)
+    }
+
+    /// Move all elements of 'other' into this heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn merge(&mut self, mut other: Self) {
+        if let Some(node) = other.root.take() {
+            self.meld(node);
+            self.len += mem::replace(&mut other.len, 0);
+        }
+    }
+}
+
+impl<T, C> Drop for PairingHeap<T, C> {
+    /// Drop the vertices iteratively since the tree can be as deep as the
+    /// number of elements.
+    fn drop(&mut self) {
+        let mut stack: Vec<Node<T>> = self.root.take().into_iter().collect();
+        while let Some(mut node) = stack.pop() {
+            stack.append(&mut node.children);
+        }
+    }
+}
+
+impl<K: Ord, V: Ord> MinPriorityQueue<K, V> for PairingHeap<(K, V), MinComparator> {
+    fn insert(&mut self, key: K, value: V) {
+        self.push((key, value));
+    }
+
+    fn extract_min(&mut self) -> Option<(K, V)> {
+        self.pop()
+    }
+}
+
+impl<T, C: Comparator<T> + Default> FromIterator<T> for PairingHeap<T, C> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut heap = PairingHeap::default();
+        heap.extend(iter);
+        heap
+    }
+}
+
+impl<T, C: Comparator<T>> Extend<T> for PairingHeap<T, C> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PairingHeap;
+    use crate::heap::MinComparator;
+
+    #[test]
+    fn it_pops_in_order() {
+        let xs = [5, 1, 4, 4, 9, 2, 7, 0, 3];
+        let mut heap: PairingHeap<i32> = xs.iter().cloned().collect();
+        assert_eq!(heap.len(), xs.len());
+        assert_eq!(heap.peek(), Some(&9));
+        let mut popped = Vec::new();
+        while let Some(x) = heap.pop() {
+            popped.push(x);
+        }
+        assert_eq!(popped, vec![9, 7, 5, 4, 4, 3, 2, 1, 0]);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn it_merges() {
+        let mut a: PairingHeap<i32, MinComparator> = (0..50).map(|i| i * 2).collect();
+        let b: PairingHeap<i32, MinComparator> = (0..50).map(|i| i * 2 + 1).collect();
+        a.merge(b);
+        a.merge(PairingHeap::new_min());
+        assert_eq!(a.len(), 100);
+        for i in 0..100 {
+            assert_eq!(a.pop(), Some(i));
+        }
+        assert_eq!(a.pop(), None);
+    }
+
+    #[test]
+    fn it_drops_deep_heap() {
+        let mut heap = PairingHeap::new();
+        heap.extend(0..1_000_000);
+        assert_eq!(heap.pop(), Some(999_999));
+    }
+}
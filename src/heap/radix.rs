@@ -0,0 +1,173 @@
+//! A radix heap for monotone priority queues over unsigned integer keys.
+
+use super::MinPriorityQueue;
+use num::{PrimInt, Unsigned};
+
+/// A min-heap of key-value pairs where keys are unsigned integers and no key
+/// smaller than the most recently popped one is ever pushed, as in Dijkstra's
+/// algorithm. Pairs are kept in buckets by the highest bit in which their key
+/// differs from the last popped key.
+pub struct RadixHeap<K: PrimInt + Unsigned, V> {
+    /// 'buckets[0]' holds keys equal to 'last', 'buckets[b]' holds keys whose
+    /// highest bit differing from 'last' is bit 'b - 1'.
+    buckets: Vec<Vec<(K, V)>>,
+    /// The most recently popped key, initially zero.
+    last: K,
+    len: usize,
+}
+
+impl<K: PrimInt + Unsigned, V> Default for RadixHeap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: PrimInt + Unsigned, V> RadixHeap<K, V> {
+    /// Create an empty radix heap.
+    ///
+    /// Time complexity: O(log K), for the number of bits of 'K'
+    pub fn new() -> Self {
+        let bits = K::zero().count_zeros() as usize;
+        RadixHeap {
+            buckets: (0..=bits).map(|_| Vec::new()).collect(),
+            last: K::zero(),
+            len: 0,
+        }
+    }
+
+    /// The number of pairs in the heap.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// True if the heap is empty, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The most recently popped key, or zero if nothing has been popped. No
+    /// smaller key may be pushed.
+    pub fn last_key(&self) -> K {
+        self.last
+    }
+
+    /// The bucket of 'key' relative to the last popped key.
+    fn bucket(&self, key: K) -> usize {
+        let bits = K::zero().count_zeros();
+        (bits - (key ^ self.last).leading_zeros()) as usize
+    }
+
+    /// Insert 'value' with priority 'key'.
+    ///
+    /// Panics if 'key' is smaller than the most recently popped key.
+    ///
+    /// Time complexity: O(1)
+    pub fn push(&mut self, key: K, value: V) {
+        assert!(key >= self.last, "key is smaller than the last popped key");
+        let b = self.bucket(key);
+        self.buckets[b].push((key, value));
+        self.len += 1;
+    }
+
+    /// Remove a pair with the smallest key. Returns the removed pair or 'None'
+    /// in case the heap was empty.
+    ///
+    /// Time complexity: O(log K) amortized, for the number of bits of 'K'
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        if self.buckets[0].is_empty() {
+            // Move the pairs of the first non-empty bucket to smaller buckets
+            // relative to its smallest key.
+            let b = self.buckets.iter().position(|bucket| !bucket.is_empty())?;
+            let mut pairs = std::mem::take(&mut self.buckets[b]);
+            self.last = pairs.iter().map(|&(k, _)| k).min().unwrap();
+            for (k, v) in pairs.drain(..) {
+                let b = self.bucket(k);
+                self.buckets[b].push((k, v));
+            }
+            // Every pair moved to a smaller bucket, so keep the allocation.
+            self.buckets[b] = pairs;
+        }
+        self.len -= 1;
+        self.buckets[0].pop()
+    }
+}
+
+impl<K: PrimInt + Unsigned, V> MinPriorityQueue<K, V> for RadixHeap<K, V> {
+    fn insert(&mut self, key: K, value: V) {
+        self.push(key, value);
+    }
+
+    fn extract_min(&mut self) -> Option<(K, V)> {
+        self.pop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RadixHeap;
+    use crate::test_util::Lcg;
+
+    #[test]
+    fn it_pops_in_order() {
+        let mut heap = RadixHeap::new();
+        for &k in [5u32, 1, 4, 4, 9, 2, 7, 0, 3].iter() {
+            heap.push(k, k * 10);
+        }
+        assert_eq!(heap.len(), 9);
+        let mut popped = Vec::new();
+        while let Some((k, v)) = heap.pop() {
+            assert_eq!(v, k * 10);
+            popped.push(k);
+        }
+        assert_eq!(popped, vec![0, 1, 2, 3, 4, 4, 5, 7, 9]);
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    fn it_handles_monotone_workload() {
+        // Push keys no smaller than the last popped key, like Dijkstra's
+        // algorithm, and check against a sorted vector.
+        let mut heap = RadixHeap::new();
+        let mut naive: Vec<u64> = Vec::new();
+        let mut rng = Lcg::new(5);
+        for step in 0..5000 {
+            if step % 3 == 2 {
+                naive.sort_unstable_by(|a, b| b.cmp(a));
+                let expected = naive.pop();
+                assert_eq!(heap.pop().map(|(k, _)| k), expected);
+            } else {
+                let k = heap.last_key() + rng.next_u64() % 1000;
+                heap.push(k, ());
+                naive.push(k);
+            }
+        }
+        assert_eq!(heap.len(), naive.len());
+    }
+
+    #[test]
+    fn it_handles_extreme_keys() {
+        let mut heap = RadixHeap::new();
+        heap.push(u8::MAX, 'a');
+        heap.push(0, 'b');
+        heap.push(128, 'c');
+        assert_eq!(heap.pop(), Some((0, 'b')));
+        assert_eq!(heap.pop(), Some((128, 'c')));
+        heap.push(200, 'd');
+        assert_eq!(heap.pop(), Some((200, 'd')));
+        assert_eq!(heap.pop(), Some((u8::MAX, 'a')));
+        assert_eq!(heap.pop(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn it_rejects_decreasing_keys() {
+        let mut heap = RadixHeap::new();
+        heap.push(5u32, ());
+        heap.pop();
+        heap.push(4, ());
+    }
+}
@@ -0,0 +1,238 @@
+//! Streaming selection built on 'Heap': the k greatest or smallest elements,
+//! merging sorted sequences and running medians.
+
+use super::{Comparator, Heap, MaxComparator, MinComparator};
+use std::cmp::Ordering;
+
+/// The 'k' greatest elements of 'iter' according to 'cmp', greatest first.
+/// Keeps a heap of at most 'k' elements whose root is the smallest of them.
+///
+/// Time complexity: O(n log k)
+pub fn top_k_by<T, I, C>(iter: I, k: usize, cmp: C) -> Vec<T>
+where
+    I: IntoIterator<Item = T>,
+    C: Comparator<T>,
+{
+    if k == 0 {
+        return Vec::new();
+    }
+    let mut heap = Heap::with_comparator(|a: &T, b: &T| cmp.compare(b, a));
+    for x in iter {
+        if heap.len() < k {
+            heap.push(x);
+        } else if let Some(mut smallest) = heap.peek_mut() {
+            if cmp.compare(&x, &smallest) == Ordering::Greater {
+                *smallest = x;
+            }
+        }
+    }
+    heap.into_sorted_vec()
+}
+
+/// The 'k' greatest elements of 'iter', greatest first.
+///
+/// Time complexity: O(n log k)
+pub fn top_k<T: Ord, I: IntoIterator<Item = T>>(iter: I, k: usize) -> Vec<T> {
+    top_k_by(iter, k, MaxComparator)
+}
+
+/// The 'k' smallest elements of 'iter', smallest first.
+///
+/// Time complexity: O(n log k)
+pub fn bottom_k<T: Ord, I: IntoIterator<Item = T>>(iter: I, k: usize) -> Vec<T> {
+    top_k_by(iter, k, MinComparator)
+}
+
+/// An iterator over the elements of several sorted iterators in sorted order,
+/// created by 'k_way_merge'.
+pub struct KWayMerge<I: Iterator>
+where
+    I::Item: Ord,
+{
+    iters: Vec<I>,
+    /// The next element of each iterator which is not exhausted, along with
+    /// the index of the iterator to break ties by.
+    heap: Heap<(I::Item, usize), MinComparator>,
+}
+
+impl<I: Iterator> Iterator for KWayMerge<I>
+where
+    I::Item: Ord,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let (x, i) = self.heap.pop()?;
+        if let Some(y) = self.iters[i].next() {
+            self.heap.push((y, i));
+        }
+        Some(x)
+    }
+}
+
+/// Merge iterators which are each sorted in ascending order into a single
+/// sorted iterator. Equal elements are returned in the order of the iterators
+/// they come from.
+///
+/// Time complexity: O(log k) per element, for 'k' iterators
+pub fn k_way_merge<J, I>(iters: J) -> KWayMerge<I::IntoIter>
+where
+    J: IntoIterator<Item = I>,
+    I: IntoIterator,
+    I::Item: Ord,
+{
+    let mut iters: Vec<I::IntoIter> = iters.into_iter().map(|i| i.into_iter()).collect();
+    let firsts = iters
+        .iter_mut()
+        .enumerate()
+        .filter_map(|(i, it)| it.next().map(|x| (x, i)))
+        .collect::<Vec<_>>();
+    KWayMerge {
+        iters,
+        heap: Heap::from(firsts),
+    }
+}
+
+/// The median of a growing sequence of elements. The smaller half is kept in a
+/// max-heap and the larger half in a min-heap.
+pub struct RunningMedian<T: Ord> {
+    /// The smaller half, including the lower median.
+    lower: Heap<T>,
+    /// The larger half, at most as large as the smaller half.
+    upper: Heap<T, MinComparator>,
+}
+
+impl<T: Ord> Default for RunningMedian<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> RunningMedian<T> {
+    /// Create a running median over no elements.
+    ///
+    /// Time complexity: O(1)
+    pub fn new() -> Self {
+        RunningMedian {
+            lower: Heap::new(),
+            upper: Heap::new_min(),
+        }
+    }
+
+    /// The number of elements seen.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.lower.len() + self.upper.len()
+    }
+
+    /// True if no elements have been seen, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.lower.is_empty()
+    }
+
+    /// Add the element 'x'.
+    ///
+    /// Time complexity: O(log n)
+    pub fn push(&mut self, x: T) {
+        let below_median = match self.lower.peek() {
+            Some(m) => x <= *m,
+            None => true,
+        };
+        if below_median {
+            self.lower.push(x);
+        } else {
+            self.upper.push(x);
+        }
+        if self.lower.len() > self.upper.len() + 1 {
+            let m = self.lower.pop().unwrap();
+            self.upper.push(m);
+        } else if self.upper.len() > self.lower.len() {
+            let m = self.upper.pop().unwrap();
+            self.lower.push(m);
+        }
+    }
+
+    /// The lower median, the element at index '(n - 1) / 2' in sorted order,
+    /// or 'None' if no elements have been seen.
+    ///
+    /// Time complexity: O(1)
+    pub fn median(&self) -> Option<&T> {
+        self.lower.peek()
+    }
+
+    /// The upper median, the element at index 'n / 2' in sorted order, or
+    /// 'None' if no elements have been seen.
+    ///
+    /// Time complexity: O(1)
+    pub fn high_median(&self) -> Option<&T> {
+        if self.upper.len() == self.lower.len() {
+            self.upper.peek()
+        } else {
+            self.lower.peek()
+        }
+    }
+}
+
+impl<T: Ord> Extend<T> for RunningMedian<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for x in iter {
+            self.push(x);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bottom_k, k_way_merge, top_k, top_k_by, RunningMedian};
+
+    #[test]
+    fn it_selects_top_and_bottom_k() {
+        let xs = [5, 1, 4, 4, 9, 2, 7, 0, 3, 8, 6];
+        assert_eq!(top_k(xs.iter().cloned(), 3), vec![9, 8, 7]);
+        assert_eq!(bottom_k(xs.iter().cloned(), 4), vec![0, 1, 2, 3]);
+        assert_eq!(top_k(xs.iter().cloned(), 0), Vec::<i32>::new());
+        assert_eq!(top_k(xs.iter().cloned(), 20).len(), xs.len());
+        assert_eq!(bottom_k(vec![2, 2, 1], 2), vec![1, 2]);
+    }
+
+    #[test]
+    fn it_selects_by_comparator() {
+        let words = vec!["pear", "fig", "banana", "kiwi", "apple"];
+        let longest = top_k_by(words, 2, |a: &&str, b: &&str| a.len().cmp(&b.len()));
+        assert_eq!(longest, vec!["banana", "apple"]);
+    }
+
+    #[test]
+    fn it_merges_sorted_iterators() {
+        let merged: Vec<i32> =
+            k_way_merge(vec![vec![1, 4, 9], vec![], vec![2, 3, 10, 11], vec![4]]).collect();
+        assert_eq!(merged, vec![1, 2, 3, 4, 4, 9, 10, 11]);
+
+        let tagged = vec![vec![(1, 'a')], vec![(0, 'b'), (1, 'b')]];
+        let merged: Vec<(i32, char)> = k_way_merge(tagged).collect();
+        assert_eq!(merged, vec![(0, 'b'), (1, 'a'), (1, 'b')]);
+
+        let none: Vec<Vec<i32>> = Vec::new();
+        assert_eq!(k_way_merge(none).next(), None);
+    }
+
+    #[test]
+    fn it_tracks_running_median() {
+        let mut running = RunningMedian::new();
+        assert_eq!(running.median(), None);
+        assert_eq!(running.high_median(), None);
+        let mut seen = Vec::new();
+        for x in (0..200).map(|i| (i * 37 + 11) % 101) {
+            running.push(x);
+            seen.push(x);
+            seen.sort_unstable();
+            let n = seen.len();
+            assert_eq!(running.len(), n);
+            assert_eq!(running.median(), Some(&seen[(n - 1) / 2]));
+            assert_eq!(running.high_median(), Some(&seen[n / 2]));
+        }
+    }
+}
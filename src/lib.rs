@@ -1,6 +1,11 @@
 pub mod disjoint_set;
+pub mod fenwick;
 pub mod graph;
 pub mod lca;
 pub mod segment_tree;
 pub mod sequence;
+pub mod sparse_table;
 pub mod heap;
+
+#[cfg(test)]
+mod test_util;
@@ -1,25 +1,53 @@
-use num::Bounded;
+use num::{Bounded, Integer, One, PrimInt, Zero};
 use std::clone::Clone;
 use std::cmp::{max, min};
+use std::iter::FromIterator;
 use std::marker::PhantomData;
+use std::ops::{Range, Sub};
+
+pub mod beats;
+pub mod iterative;
+pub mod lazy;
+pub mod li_chao;
+pub mod merge_sort;
+pub mod persistent;
+pub mod sparse;
+pub mod two_dimensional;
+pub mod wavelet;
 
 /// A segment tree is defined by the elements it operates on, the default value
 /// of the elements, and the associative function used to combine them.
+///
+/// The specification is stored inside the segment tree, so it may carry
+/// runtime state such as a modulus.
 pub trait SegmentTreeSpec {
     type T: Clone;
-    fn default() -> Self::T;
-    fn combine(a: Self::T, b: Self::T) -> Self::T;
+    fn default(&self) -> Self::T;
+    fn combine(&self, a: Self::T, b: Self::T) -> Self::T;
+}
+
+/// A segment tree specification where 'combine' can be undone, that is the
+/// elements form a commutative group.
+pub trait InvertibleSpec: SegmentTreeSpec {
+    /// The value 'c' such that 'combine(b, c) == a'.
+    fn uncombine(&self, a: Self::T, b: Self::T) -> Self::T;
 }
 
 /// Segment tree specification for computing range minimum queries.
 pub struct MinSpec<T>(PhantomData<T>);
 
+impl<T> Default for MinSpec<T> {
+    fn default() -> Self {
+        MinSpec(PhantomData)
+    }
+}
+
 impl<T: Bounded + Ord + Clone> SegmentTreeSpec for MinSpec<T> {
     type T = T;
-    fn default() -> T {
+    fn default(&self) -> T {
         T::max_value()
     }
-    fn combine(a: T, b: T) -> T {
+    fn combine(&self, a: T, b: T) -> T {
         min(a, b)
     }
 }
@@ -27,16 +55,224 @@ impl<T: Bounded + Ord + Clone> SegmentTreeSpec for MinSpec<T> {
 /// Segment tree specification for computing range maximum queries.
 pub struct MaxSpec<T>(PhantomData<T>);
 
+impl<T> Default for MaxSpec<T> {
+    fn default() -> Self {
+        MaxSpec(PhantomData)
+    }
+}
+
 impl<T: Bounded + Ord + Clone> SegmentTreeSpec for MaxSpec<T> {
     type T = T;
-    fn default() -> T {
+    fn default(&self) -> T {
         T::min_value()
     }
-    fn combine(a: T, b: T) -> T {
+    fn combine(&self, a: T, b: T) -> T {
         max(a, b)
     }
 }
 
+/// Segment tree specification for computing range sums.
+pub struct SumSpec<T>(PhantomData<T>);
+
+impl<T> Default for SumSpec<T> {
+    fn default() -> Self {
+        SumSpec(PhantomData)
+    }
+}
+
+impl<T: Zero + Clone> SegmentTreeSpec for SumSpec<T> {
+    type T = T;
+    fn default(&self) -> T {
+        T::zero()
+    }
+    fn combine(&self, a: T, b: T) -> T {
+        a + b
+    }
+}
+
+impl<T: Zero + Sub<Output = T> + Clone> InvertibleSpec for SumSpec<T> {
+    fn uncombine(&self, a: T, b: T) -> T {
+        a - b
+    }
+}
+
+/// Segment tree specification for computing range products.
+pub struct ProductSpec<T>(PhantomData<T>);
+
+impl<T> Default for ProductSpec<T> {
+    fn default() -> Self {
+        ProductSpec(PhantomData)
+    }
+}
+
+impl<T: One + Clone> SegmentTreeSpec for ProductSpec<T> {
+    type T = T;
+    fn default(&self) -> T {
+        T::one()
+    }
+    fn combine(&self, a: T, b: T) -> T {
+        a * b
+    }
+}
+
+/// Segment tree specification for computing the greatest common divisor of
+/// a range.
+pub struct GcdSpec<T>(PhantomData<T>);
+
+impl<T> Default for GcdSpec<T> {
+    fn default() -> Self {
+        GcdSpec(PhantomData)
+    }
+}
+
+impl<T: Integer + Clone> SegmentTreeSpec for GcdSpec<T> {
+    type T = T;
+    fn default(&self) -> T {
+        T::zero()
+    }
+    fn combine(&self, a: T, b: T) -> T {
+        a.gcd(&b)
+    }
+}
+
+/// Segment tree specification for computing the bitwise xor of a range.
+pub struct XorSpec<T>(PhantomData<T>);
+
+impl<T> Default for XorSpec<T> {
+    fn default() -> Self {
+        XorSpec(PhantomData)
+    }
+}
+
+impl<T: PrimInt> SegmentTreeSpec for XorSpec<T> {
+    type T = T;
+    fn default(&self) -> T {
+        T::zero()
+    }
+    fn combine(&self, a: T, b: T) -> T {
+        a ^ b
+    }
+}
+
+impl<T: PrimInt> InvertibleSpec for XorSpec<T> {
+    fn uncombine(&self, a: T, b: T) -> T {
+        a ^ b
+    }
+}
+
+/// Segment tree specification for computing the bitwise or of a range.
+pub struct OrSpec<T>(PhantomData<T>);
+
+impl<T> Default for OrSpec<T> {
+    fn default() -> Self {
+        OrSpec(PhantomData)
+    }
+}
+
+impl<T: PrimInt> SegmentTreeSpec for OrSpec<T> {
+    type T = T;
+    fn default(&self) -> T {
+        T::zero()
+    }
+    fn combine(&self, a: T, b: T) -> T {
+        a | b
+    }
+}
+
+/// Segment tree specification for computing the bitwise and of a range.
+pub struct AndSpec<T>(PhantomData<T>);
+
+impl<T> Default for AndSpec<T> {
+    fn default() -> Self {
+        AndSpec(PhantomData)
+    }
+}
+
+impl<T: PrimInt> SegmentTreeSpec for AndSpec<T> {
+    type T = T;
+    fn default(&self) -> T {
+        !T::zero()
+    }
+    fn combine(&self, a: T, b: T) -> T {
+        a & b
+    }
+}
+
+/// Segment tree specification for finding the position of the minimum in a
+/// range. Elements are pairs '(i, x)' of an index and a value. Ties are broken
+/// by choosing the smallest index.
+pub struct ArgMinSpec<T>(PhantomData<T>);
+
+impl<T> Default for ArgMinSpec<T> {
+    fn default() -> Self {
+        ArgMinSpec(PhantomData)
+    }
+}
+
+impl<T: Bounded + Ord + Clone> SegmentTreeSpec for ArgMinSpec<T> {
+    type T = (usize, T);
+    fn default(&self) -> (usize, T) {
+        (usize::MAX, T::max_value())
+    }
+    fn combine(&self, a: (usize, T), b: (usize, T)) -> (usize, T) {
+        if (&b.1, b.0) < (&a.1, a.0) {
+            b
+        } else {
+            a
+        }
+    }
+}
+
+/// Segment tree specification for finding the position of the maximum in a
+/// range. Elements are pairs '(i, x)' of an index and a value. Ties are broken
+/// by choosing the smallest index.
+pub struct ArgMaxSpec<T>(PhantomData<T>);
+
+impl<T> Default for ArgMaxSpec<T> {
+    fn default() -> Self {
+        ArgMaxSpec(PhantomData)
+    }
+}
+
+impl<T: Bounded + Ord + Clone> SegmentTreeSpec for ArgMaxSpec<T> {
+    type T = (usize, T);
+    fn default(&self) -> (usize, T) {
+        (usize::MAX, T::min_value())
+    }
+    fn combine(&self, a: (usize, T), b: (usize, T)) -> (usize, T) {
+        if b.1 > a.1 || (b.1 == a.1 && b.0 < a.0) {
+            b
+        } else {
+            a
+        }
+    }
+}
+
+/// Segment tree specification given by an identity element and an associative
+/// function, for example multiplication modulo a prime chosen at runtime.
+pub struct FnSpec<T, F> {
+    identity: T,
+    f: F,
+}
+
+impl<T: Clone, F: Fn(T, T) -> T> FnSpec<T, F> {
+    /// Create a specification where 'identity' is the default value and 'f'
+    /// combines two values.
+    pub fn new(identity: T, f: F) -> Self {
+        FnSpec { identity, f }
+    }
+}
+
+impl<T: Clone, F: Fn(T, T) -> T> SegmentTreeSpec for FnSpec<T, F> {
+    type T = T;
+    fn default(&self) -> T {
+        self.identity.clone()
+    }
+    fn combine(&self, a: T, b: T) -> T {
+        (self.f)(a, b)
+    }
+}
+
 /// Compute the closest power of two 'x' of 'n' such that 'n <= x'. If 'n' is a
 /// power of two then 'x = n'.
 fn next_2pow(n: usize) -> usize {
@@ -47,9 +283,28 @@ fn next_2pow(n: usize) -> usize {
     x
 }
 
+/// Compute the bound '[l,r)' covered by each vertex of a segment tree over 'n'
+/// values. The children of vertex 'i' are '2i + 1' and '2i + 2', unused
+/// vertices have bound '(0, 0)'.
+fn vertex_bounds(n: usize) -> Vec<(usize, usize)> {
+    let mut bounds = vec![(0, 0); 2 * next_2pow(n) - 1];
+    let mut stack = vec![(0, 0, n)];
+    while let Some((idx, l, r)) = stack.pop() {
+        bounds[idx] = (l, r);
+        if r - l > 1 {
+            let m = l + (r - l) / 2;
+            stack.push((idx * 2 + 1, l, m));
+            stack.push((idx * 2 + 2, m, r));
+        }
+    }
+    bounds
+}
+
 /// A data-structure which supports updating and quering sub-ranges over its
 /// elements.
 pub struct SegmentTree<Spec: SegmentTreeSpec> {
+    /// The specification defining the default value and how to combine values.
+    spec: Spec,
     /// The values of each respective vertex in the tree.
     values: Vec<Spec::T>,
     /// For each vertex in the tree their bound '[l,r)' represents the range it
@@ -57,31 +312,238 @@ pub struct SegmentTree<Spec: SegmentTreeSpec> {
     bounds: Vec<(usize, usize)>,
 }
 
-impl<Spec: SegmentTreeSpec> SegmentTree<Spec> {
-    /// Create a segment tree over 'n' values.
+impl<Spec: SegmentTreeSpec + Default> SegmentTree<Spec> {
+    /// Create a segment tree over 'n' values, each set to 'Spec::default()'.
+    ///
+    /// Time complexity: O(n)
     pub fn new(n: usize) -> Self {
-        let tree_size = 2 * next_2pow(n) - 1;
-        let mut t = SegmentTree {
-            values: vec![Spec::default(); tree_size],
-            bounds: vec![(0, 0); tree_size],
-        };
-        let mut stack = vec![(0, 0, n)];
-        while let Some((idx, l, r)) = stack.pop() {
-            t.bounds[idx] = (l, r);
-            if r - l > 1 {
-                let m = l + (r - l) / 2;
-                stack.push((idx * 2 + 1, l, m));
-                stack.push((idx * 2 + 2, m, r));
+        SegmentTree::with_spec(Default::default(), n)
+    }
+
+    /// Create a segment tree over the values in 'xs'.
+    ///
+    /// Time complexity: O(n)
+    pub fn from_slice(xs: &[Spec::T]) -> Self {
+        SegmentTree::from_slice_with_spec(Default::default(), xs)
+    }
+}
+
+impl<Spec: SegmentTreeSpec> SegmentTree<Spec> {
+    /// Create a segment tree over 'n' values using specification 'spec'. Each
+    /// value is set to 'spec.default()'.
+    ///
+    /// Time complexity: O(n)
+    pub fn with_spec(spec: Spec, n: usize) -> Self {
+        let bounds = vertex_bounds(n);
+        SegmentTree {
+            values: vec![spec.default(); bounds.len()],
+            spec,
+            bounds,
+        }
+    }
+
+    /// Create a segment tree over the values in 'xs' using specification
+    /// 'spec'.
+    ///
+    /// Time complexity: O(n)
+    pub fn from_slice_with_spec(spec: Spec, xs: &[Spec::T]) -> Self {
+        let mut t = SegmentTree::with_spec(spec, xs.len());
+        // Children are always stored after their parent, so iterating in
+        // reverse visits every vertex after both of its children.
+        for idx in (0..t.values.len()).rev() {
+            let (l, r) = t.bounds[idx];
+            if r - l == 1 {
+                t.values[idx] = xs[l].clone();
+            } else if r - l > 1 {
+                t.pull(idx);
             }
         }
         t
     }
+
+    /// The specification the segment tree was created with.
+    pub fn spec(&self) -> &Spec {
+        &self.spec
+    }
+
+    /// The number of values the segment tree is built over.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.bounds[0].1
+    }
+
+    /// True if the segment tree is built over zero values, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Recompute the value of vertex 'idx' from its children.
+    fn pull(&mut self, idx: usize) {
+        self.values[idx] = self.spec.combine(
+            self.values[idx * 2 + 1].clone(),
+            self.values[idx * 2 + 2].clone(),
+        );
+    }
+
+    /// Set the value at index 'i' to 'x'.
+    ///
+    /// Panics if 'i' is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn update(&mut self, i: usize, x: Spec::T) {
+        assert!(i < self.len(), "index {} out of bounds", i);
+        let mut path = Vec::new();
+        let mut idx = 0;
+        loop {
+            let (l, r) = self.bounds[idx];
+            if r - l == 1 {
+                break;
+            }
+            path.push(idx);
+            let m = l + (r - l) / 2;
+            idx = if i < m { idx * 2 + 1 } else { idx * 2 + 2 };
+        }
+        self.values[idx] = x;
+        while let Some(idx) = path.pop() {
+            self.pull(idx);
+        }
+    }
+
+    /// Combine the values in the range '[l,r)'. Returns 'spec.default()' if
+    /// the range is empty.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn query(&self, range: Range<usize>) -> Spec::T {
+        let Range { start, end } = range;
+        assert!(
+            start <= end && end <= self.len(),
+            "range {}..{} out of bounds",
+            start,
+            end
+        );
+        self.query_rec(0, start, end)
+    }
+
+    /// Combine the values in '[l,r)' which are covered by vertex 'idx'.
+    fn query_rec(&self, idx: usize, l: usize, r: usize) -> Spec::T {
+        let (vl, vr) = self.bounds[idx];
+        if r <= vl || vr <= l {
+            self.spec.default()
+        } else if l <= vl && vr <= r {
+            self.values[idx].clone()
+        } else {
+            self.spec.combine(
+                self.query_rec(idx * 2 + 1, l, r),
+                self.query_rec(idx * 2 + 2, l, r),
+            )
+        }
+    }
+
+    /// Find the largest 'r' such that 'f' holds for the combined value of
+    /// '[l,r)'. The predicate must hold for 'spec.default()' and be monotone:
+    /// once it fails for '[l,r)' it fails for every larger 'r'.
+    ///
+    /// Panics if 'l' is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn max_right<F: Fn(&Spec::T) -> bool>(&self, l: usize, f: F) -> usize {
+        assert!(l <= self.len(), "index {} out of bounds", l);
+        let mut acc = self.spec.default();
+        self.max_right_rec(0, l, &f, &mut acc)
+            .unwrap_or_else(|| self.len())
+    }
+
+    /// Extend 'acc' with the values in '[l,n)' covered by vertex 'idx' while
+    /// 'f' holds. Returns the index of the first value which makes 'f' fail.
+    fn max_right_rec<F: Fn(&Spec::T) -> bool>(
+        &self,
+        idx: usize,
+        l: usize,
+        f: &F,
+        acc: &mut Spec::T,
+    ) -> Option<usize> {
+        let (vl, vr) = self.bounds[idx];
+        if vr <= l {
+            return None;
+        }
+        if l <= vl {
+            let x = self.spec.combine(acc.clone(), self.values[idx].clone());
+            if f(&x) {
+                *acc = x;
+                return None;
+            }
+            if vr - vl == 1 {
+                return Some(vl);
+            }
+        }
+        self.max_right_rec(idx * 2 + 1, l, f, acc)
+            .or_else(|| self.max_right_rec(idx * 2 + 2, l, f, acc))
+    }
+
+    /// Find the smallest 'l' such that 'f' holds for the combined value of
+    /// '[l,r)'. The predicate must hold for 'spec.default()' and be monotone:
+    /// once it fails for '[l,r)' it fails for every smaller 'l'.
+    ///
+    /// Panics if 'r' is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn min_left<F: Fn(&Spec::T) -> bool>(&self, r: usize, f: F) -> usize {
+        assert!(r <= self.len(), "index {} out of bounds", r);
+        let mut acc = self.spec.default();
+        self.min_left_rec(0, r, &f, &mut acc).unwrap_or(0)
+    }
+
+    /// Extend 'acc' with the values in '[0,r)' covered by vertex 'idx', from
+    /// right to left, while 'f' holds. Returns the index after the first value
+    /// which makes 'f' fail.
+    fn min_left_rec<F: Fn(&Spec::T) -> bool>(
+        &self,
+        idx: usize,
+        r: usize,
+        f: &F,
+        acc: &mut Spec::T,
+    ) -> Option<usize> {
+        let (vl, vr) = self.bounds[idx];
+        if r <= vl {
+            return None;
+        }
+        if vr <= r {
+            let x = self.spec.combine(self.values[idx].clone(), acc.clone());
+            if f(&x) {
+                *acc = x;
+                return None;
+            }
+            if vr - vl == 1 {
+                return Some(vr);
+            }
+        }
+        self.min_left_rec(idx * 2 + 2, r, f, acc)
+            .or_else(|| self.min_left_rec(idx * 2 + 1, r, f, acc))
+    }
+}
+
+impl<Spec: SegmentTreeSpec + Default> FromIterator<Spec::T> for SegmentTree<Spec> {
+    fn from_iter<I: IntoIterator<Item = Spec::T>>(iter: I) -> Self {
+        let xs: Vec<Spec::T> = iter.into_iter().collect();
+        SegmentTree::from_slice(&xs)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::FnSpec;
+    use super::MaxSpec;
     use super::MinSpec;
     use super::SegmentTree;
+    use super::SegmentTreeSpec;
+    use super::{AndSpec, OrSpec, XorSpec};
+    use super::{ArgMaxSpec, ArgMinSpec};
+    use super::{GcdSpec, ProductSpec, SumSpec};
 
     #[test]
     fn it_compiles() {
@@ -96,4 +558,178 @@ mod tests {
             vec![(0, 3), (0, 1), (1, 3), (0, 0), (0, 0), (1, 2), (2, 3)]
         )
     }
+
+    #[test]
+    fn it_builds_from_slice() {
+        let t = SegmentTree::<MinSpec<i32>>::from_slice(&[5, 3, 7]);
+        let m = i32::MAX;
+        assert_eq!(t.values, vec![3, 5, 3, m, m, 3, 7]);
+    }
+
+    #[test]
+    fn it_answers_queries() {
+        let xs = [4, 2, 7, 1, 9, 3, 3, 8];
+        let t: SegmentTree<MinSpec<i32>> = xs.iter().cloned().collect();
+        for l in 0..xs.len() {
+            for r in l + 1..=xs.len() {
+                assert_eq!(t.query(l..r), *xs[l..r].iter().min().unwrap());
+            }
+        }
+        assert_eq!(t.query(3..3), i32::MAX);
+    }
+
+    #[test]
+    fn it_updates_values() {
+        let mut t = SegmentTree::<MaxSpec<i32>>::new(5);
+        assert_eq!(t.query(0..5), i32::MIN);
+        t.update(3, 10);
+        t.update(1, 4);
+        assert_eq!(t.query(0..5), 10);
+        assert_eq!(t.query(0..3), 4);
+        t.update(3, -1);
+        assert_eq!(t.query(2..5), -1);
+        assert_eq!(t.query(0..5), 4);
+    }
+
+    #[test]
+    fn it_handles_empty_tree() {
+        let t = SegmentTree::<MinSpec<i32>>::from_slice(&[]);
+        assert!(t.is_empty());
+        assert_eq!(t.query(0..0), i32::MAX);
+    }
+
+    #[test]
+    fn it_uses_runtime_modulus() {
+        let p = 1_000_000_007u64;
+        let xs = [123_456_789, 987_654_321, 555_555_555, 2];
+        let t = SegmentTree::from_slice_with_spec(FnSpec::new(1, move |a, b| a * b % p), &xs);
+        let expected = xs.iter().fold(1, |acc, &x| acc * x % p);
+        assert_eq!(t.query(0..4), expected);
+        assert_eq!(t.query(2..4), 111_111_103);
+        assert_eq!(t.query(1..1), 1);
+    }
+
+    #[test]
+    fn it_multiplies_matrices_in_order() {
+        type Matrix = [[i64; 2]; 2];
+        let mul = |a: Matrix, b: Matrix| {
+            let mut c = [[0; 2]; 2];
+            for i in 0..2 {
+                for j in 0..2 {
+                    for k in 0..2 {
+                        c[i][j] += a[i][k] * b[k][j];
+                    }
+                }
+            }
+            c
+        };
+        let spec = FnSpec::new([[1, 0], [0, 1]], mul);
+        let mut t = SegmentTree::with_spec(spec, 6);
+        for i in 0..6 {
+            t.update(i, [[1, 1], [1, 0]]);
+        }
+        // Powers of the Fibonacci matrix.
+        assert_eq!(t.query(0..6), [[13, 8], [8, 5]]);
+        t.update(2, [[0, 1], [1, 0]]);
+        assert_eq!(t.query(1..4), [[2, 1], [1, 0]]);
+    }
+
+    /// Check every range query of a segment tree over 'xs' against folding the
+    /// range directly.
+    fn check_all_ranges<Spec>(xs: &[Spec::T])
+    where
+        Spec: SegmentTreeSpec + Default,
+        Spec::T: PartialEq + std::fmt::Debug,
+    {
+        let t = SegmentTree::<Spec>::from_slice(xs);
+        let spec = t.spec();
+        for l in 0..=xs.len() {
+            for r in l..=xs.len() {
+                let expected = xs[l..r]
+                    .iter()
+                    .fold(spec.default(), |acc, x| spec.combine(acc, x.clone()));
+                assert_eq!(t.query(l..r), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn it_answers_arithmetic_queries() {
+        let xs = [12, 18, -6, 4, 30, 0, 9, 27];
+        check_all_ranges::<SumSpec<i64>>(&xs);
+        check_all_ranges::<ProductSpec<i64>>(&xs);
+        check_all_ranges::<GcdSpec<i64>>(&xs);
+
+        let t = SegmentTree::<GcdSpec<i64>>::from_slice(&xs);
+        assert_eq!(t.query(0..3), 6);
+        assert_eq!(t.query(6..8), 9);
+    }
+
+    #[test]
+    fn it_answers_bitwise_queries() {
+        let xs = [0b1100u8, 0b1010, 0b0110, 0b1111, 0b0001];
+        check_all_ranges::<XorSpec<u8>>(&xs);
+        check_all_ranges::<OrSpec<u8>>(&xs);
+        check_all_ranges::<AndSpec<u8>>(&xs);
+
+        let t = SegmentTree::<AndSpec<u8>>::from_slice(&xs);
+        assert_eq!(t.query(0..2), 0b1000);
+        assert_eq!(t.query(2..2), 0xff);
+    }
+
+    #[test]
+    fn it_finds_arg_min_and_max() {
+        let xs = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        let min_t: SegmentTree<ArgMinSpec<i32>> = xs.iter().cloned().enumerate().collect();
+        let max_t: SegmentTree<ArgMaxSpec<i32>> = xs.iter().cloned().enumerate().collect();
+        for l in 0..xs.len() {
+            for r in l + 1..=xs.len() {
+                let min = *xs[l..r].iter().min().unwrap();
+                let max = *xs[l..r].iter().max().unwrap();
+                let min_i = l + xs[l..r].iter().position(|&x| x == min).unwrap();
+                let max_i = l + xs[l..r].iter().position(|&x| x == max).unwrap();
+                assert_eq!(min_t.query(l..r), (min_i, min));
+                assert_eq!(max_t.query(l..r), (max_i, max));
+            }
+        }
+    }
+
+    #[test]
+    fn it_descends_to_max_right() {
+        let xs = [3, 1, 4, 1, 5, 9, 2, 6];
+        let t = SegmentTree::<SumSpec<i32>>::from_slice(&xs);
+        for l in 0..=xs.len() {
+            for k in 0..30 {
+                let mut r = l;
+                while r < xs.len() && xs[l..=r].iter().sum::<i32>() <= k {
+                    r += 1;
+                }
+                assert_eq!(t.max_right(l, |&s| s <= k), r);
+            }
+        }
+        // First position where the prefix sum exceeds 8.
+        assert_eq!(t.max_right(0, |&s| s <= 8), 3);
+    }
+
+    #[test]
+    fn it_descends_to_min_left() {
+        let xs = [3, 1, 4, 1, 5, 9, 2, 6];
+        let t = SegmentTree::<MaxSpec<i32>>::from_slice(&xs);
+        for r in 0..=xs.len() {
+            for k in 0..10 {
+                let mut l = r;
+                while l > 0 && xs[l - 1] <= k {
+                    l -= 1;
+                }
+                assert_eq!(t.min_left(r, |&m| m <= k), l);
+            }
+        }
+    }
+
+    #[test]
+    fn it_descends_in_empty_tree() {
+        let t = SegmentTree::<SumSpec<i32>>::new(0);
+        assert_eq!(t.max_right(0, |&s| s <= 0), 0);
+        assert_eq!(t.min_left(0, |&s| s <= 0), 0);
+    }
 }
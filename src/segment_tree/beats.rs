@@ -0,0 +1,448 @@
+//! Segment Tree Beats, a segment tree supporting range chmin and chmax updates
+//! by only descending into vertices where the update affects more than the
+//! largest or smallest values.
+
+use super::vertex_bounds;
+use num::PrimInt;
+use std::cmp::{max, min};
+use std::ops::Range;
+
+/// Summary of the values covered by a vertex.
+#[derive(Clone)]
+struct Node<T> {
+    sum: T,
+    /// Largest value and how many times it occurs.
+    max: (T, usize),
+    /// Strictly second largest value or 'None' if all values are equal.
+    second_max: Option<T>,
+    /// Smallest value and how many times it occurs.
+    min: (T, usize),
+    /// Strictly second smallest value or 'None' if all values are equal.
+    second_min: Option<T>,
+    /// Addition which has not yet been pushed down to the children.
+    add: T,
+}
+
+impl<T: PrimInt> Node<T> {
+    /// A vertex covering the single value 'x'.
+    fn leaf(x: T) -> Self {
+        Node {
+            sum: x,
+            max: (x, 1),
+            second_max: None,
+            min: (x, 1),
+            second_min: None,
+            add: T::zero(),
+        }
+    }
+
+    /// Add 'x' to every one of the 'len' values covered by the vertex.
+    fn apply_add(&mut self, x: T, len: usize) {
+        self.sum = self.sum + x * count(len);
+        self.max.0 = self.max.0 + x;
+        self.second_max = self.second_max.map(|y| y + x);
+        self.min.0 = self.min.0 + x;
+        self.second_min = self.second_min.map(|y| y + x);
+        self.add = self.add + x;
+    }
+
+    /// Lower the largest values covered by the vertex to 'x'. Requires 'x' to
+    /// be larger than the second largest value.
+    fn apply_chmin(&mut self, x: T) {
+        if x >= self.max.0 {
+            return;
+        }
+        self.sum = self.sum - (self.max.0 - x) * count(self.max.1);
+        if self.min.0 == self.max.0 {
+            self.min.0 = x;
+        } else if self.second_min == Some(self.max.0) {
+            self.second_min = Some(x);
+        }
+        self.max.0 = x;
+    }
+
+    /// Raise the smallest values covered by the vertex to 'x'. Requires 'x' to
+    /// be smaller than the second smallest value.
+    fn apply_chmax(&mut self, x: T) {
+        if x <= self.min.0 {
+            return;
+        }
+        self.sum = self.sum + (x - self.min.0) * count(self.min.1);
+        if self.max.0 == self.min.0 {
+            self.max.0 = x;
+        } else if self.second_max == Some(self.min.0) {
+            self.second_max = Some(x);
+        }
+        self.min.0 = x;
+    }
+
+    /// Apply the pending updates of this vertex to 'child', which covers 'len'
+    /// values. Pending chmin and chmax updates are implied by the largest and
+    /// smallest values of this vertex.
+    fn push_to(&self, child: &mut Node<T>, len: usize) {
+        if self.add != T::zero() {
+            child.apply_add(self.add, len);
+        }
+        child.apply_chmin(self.max.0);
+        child.apply_chmax(self.min.0);
+    }
+}
+
+/// Combine the largest and second largest values of two vertices.
+fn merge_max<T: PrimInt>(a: &Node<T>, b: &Node<T>) -> ((T, usize), Option<T>) {
+    if a.max.0 == b.max.0 {
+        (
+            (a.max.0, a.max.1 + b.max.1),
+            max(a.second_max, b.second_max),
+        )
+    } else if a.max.0 > b.max.0 {
+        (a.max, max(a.second_max, Some(b.max.0)))
+    } else {
+        (b.max, max(Some(a.max.0), b.second_max))
+    }
+}
+
+/// Combine the smallest and second smallest values of two vertices.
+fn merge_min<T: PrimInt>(a: &Node<T>, b: &Node<T>) -> ((T, usize), Option<T>) {
+    let min_opt = |x: Option<T>, y: Option<T>| match (x, y) {
+        (Some(x), Some(y)) => Some(min(x, y)),
+        (x, None) => x,
+        (None, y) => y,
+    };
+    if a.min.0 == b.min.0 {
+        (
+            (a.min.0, a.min.1 + b.min.1),
+            min_opt(a.second_min, b.second_min),
+        )
+    } else if a.min.0 < b.min.0 {
+        (a.min, min_opt(a.second_min, Some(b.min.0)))
+    } else {
+        (b.min, min_opt(Some(a.min.0), b.second_min))
+    }
+}
+
+/// Convert a count to 'T'.
+fn count<T: PrimInt>(n: usize) -> T {
+    T::from(n).expect("count not representable")
+}
+
+/// A segment tree over integers supporting range chmin, chmax and add updates
+/// together with range sum, minimum and maximum queries.
+///
+/// Mixing chmin and chmax with range additions runs in amortized O(log^2 n)
+/// time per update.
+pub struct BeatsSegmentTree<T: PrimInt> {
+    /// The summary of each respective vertex in the tree.
+    nodes: Vec<Node<T>>,
+    /// For each vertex in the tree their bound '[l,r)' represents the range it
+    /// covers.
+    bounds: Vec<(usize, usize)>,
+}
+
+impl<T: PrimInt> BeatsSegmentTree<T> {
+    /// Create a segment tree over 'n' values, each set to zero.
+    ///
+    /// Time complexity: O(n)
+    pub fn new(n: usize) -> Self {
+        BeatsSegmentTree::from_slice(&vec![T::zero(); n])
+    }
+
+    /// Create a segment tree over the values in 'xs'.
+    ///
+    /// Time complexity: O(n)
+    pub fn from_slice(xs: &[T]) -> Self {
+        let bounds = vertex_bounds(xs.len());
+        let mut t = BeatsSegmentTree {
+            nodes: vec![Node::leaf(T::zero()); bounds.len()],
+            bounds,
+        };
+        for idx in (0..t.nodes.len()).rev() {
+            let (l, r) = t.bounds[idx];
+            if r - l == 1 {
+                t.nodes[idx] = Node::leaf(xs[l]);
+            } else if r - l > 1 {
+                t.pull(idx);
+            }
+        }
+        t
+    }
+
+    /// The number of values the segment tree is built over.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.bounds[0].1
+    }
+
+    /// True if the segment tree is built over zero values, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Recompute the summary of vertex 'idx' from its children.
+    fn pull(&mut self, idx: usize) {
+        let a = &self.nodes[idx * 2 + 1];
+        let b = &self.nodes[idx * 2 + 2];
+        let sum = a.sum + b.sum;
+        let (max, second_max) = merge_max(a, b);
+        let (min, second_min) = merge_min(a, b);
+        let node = &mut self.nodes[idx];
+        node.sum = sum;
+        node.max = max;
+        node.second_max = second_max;
+        node.min = min;
+        node.second_min = second_min;
+    }
+
+    /// Add 'x' to every value covered by vertex 'idx'.
+    fn apply_add(&mut self, idx: usize, x: T) {
+        let (l, r) = self.bounds[idx];
+        self.nodes[idx].apply_add(x, r - l);
+    }
+
+    /// Push the pending updates of vertex 'idx' down to its children.
+    fn push(&mut self, idx: usize) {
+        let node = self.nodes[idx].clone();
+        for child in [idx * 2 + 1, idx * 2 + 2].iter().cloned() {
+            let (l, r) = self.bounds[child];
+            node.push_to(&mut self.nodes[child], r - l);
+        }
+        self.nodes[idx].add = T::zero();
+    }
+
+    /// Assert that 'range' is within bounds.
+    fn check_range(&self, range: &Range<usize>) {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range {}..{} out of bounds",
+            range.start,
+            range.end
+        );
+    }
+
+    /// Set every value 'y' in the range '[l,r)' to 'min(y, x)'.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: amortized O(log^2 n)
+    pub fn chmin(&mut self, range: Range<usize>, x: T) {
+        self.check_range(&range);
+        self.chmin_rec(0, range.start, range.end, x);
+    }
+
+    /// Apply chmin with 'x' to the values in '[l,r)' covered by vertex 'idx'.
+    fn chmin_rec(&mut self, idx: usize, l: usize, r: usize, x: T) {
+        let (vl, vr) = self.bounds[idx];
+        if r <= vl || vr <= l || self.nodes[idx].max.0 <= x {
+            return;
+        }
+        let below_second = match self.nodes[idx].second_max {
+            Some(y) => y < x,
+            None => true,
+        };
+        if l <= vl && vr <= r && below_second {
+            self.nodes[idx].apply_chmin(x);
+        } else {
+            self.push(idx);
+            self.chmin_rec(idx * 2 + 1, l, r, x);
+            self.chmin_rec(idx * 2 + 2, l, r, x);
+            self.pull(idx);
+        }
+    }
+
+    /// Set every value 'y' in the range '[l,r)' to 'max(y, x)'.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: amortized O(log^2 n)
+    pub fn chmax(&mut self, range: Range<usize>, x: T) {
+        self.check_range(&range);
+        self.chmax_rec(0, range.start, range.end, x);
+    }
+
+    /// Apply chmax with 'x' to the values in '[l,r)' covered by vertex 'idx'.
+    fn chmax_rec(&mut self, idx: usize, l: usize, r: usize, x: T) {
+        let (vl, vr) = self.bounds[idx];
+        if r <= vl || vr <= l || self.nodes[idx].min.0 >= x {
+            return;
+        }
+        let above_second = match self.nodes[idx].second_min {
+            Some(y) => y > x,
+            None => true,
+        };
+        if l <= vl && vr <= r && above_second {
+            self.nodes[idx].apply_chmax(x);
+        } else {
+            self.push(idx);
+            self.chmax_rec(idx * 2 + 1, l, r, x);
+            self.chmax_rec(idx * 2 + 2, l, r, x);
+            self.pull(idx);
+        }
+    }
+
+    /// Add 'x' to every value in the range '[l,r)'.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn add(&mut self, range: Range<usize>, x: T) {
+        self.check_range(&range);
+        self.add_rec(0, range.start, range.end, x);
+    }
+
+    /// Add 'x' to the values in '[l,r)' covered by vertex 'idx'.
+    fn add_rec(&mut self, idx: usize, l: usize, r: usize, x: T) {
+        let (vl, vr) = self.bounds[idx];
+        if r <= vl || vr <= l || r <= l {
+            return;
+        }
+        if l <= vl && vr <= r {
+            self.apply_add(idx, x);
+        } else {
+            self.push(idx);
+            self.add_rec(idx * 2 + 1, l, r, x);
+            self.add_rec(idx * 2 + 2, l, r, x);
+            self.pull(idx);
+        }
+    }
+
+    /// Fold 'f' over the summaries of the vertices exactly covering '[l,r)'.
+    /// 'node' is the summary of vertex 'idx' with the pending updates of its
+    /// ancestors applied. Children are updated on copies rather than pushed
+    /// down, so queries leave the tree unchanged.
+    fn fold<A, F: Fn(A, &Node<T>) -> A>(
+        &self,
+        idx: usize,
+        node: &Node<T>,
+        l: usize,
+        r: usize,
+        acc: A,
+        f: &F,
+    ) -> A {
+        let (vl, vr) = self.bounds[idx];
+        if r <= vl || vr <= l || r <= l {
+            acc
+        } else if l <= vl && vr <= r {
+            f(acc, node)
+        } else {
+            let mut acc = acc;
+            for child in [idx * 2 + 1, idx * 2 + 2].iter().cloned() {
+                let (cl, cr) = self.bounds[child];
+                let mut summary = self.nodes[child].clone();
+                node.push_to(&mut summary, cr - cl);
+                acc = self.fold(child, &summary, l, r, acc, f);
+            }
+            acc
+        }
+    }
+
+    /// The sum of the values in the range '[l,r)'.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn sum(&self, range: Range<usize>) -> T {
+        self.check_range(&range);
+        self.fold(
+            0,
+            &self.nodes[0],
+            range.start,
+            range.end,
+            T::zero(),
+            &|acc, node| acc + node.sum,
+        )
+    }
+
+    /// The largest value in the range '[l,r)' or 'T::min_value()' if the range
+    /// is empty.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn max(&self, range: Range<usize>) -> T {
+        self.check_range(&range);
+        self.fold(
+            0,
+            &self.nodes[0],
+            range.start,
+            range.end,
+            T::min_value(),
+            &|acc, node| max(acc, node.max.0),
+        )
+    }
+
+    /// The smallest value in the range '[l,r)' or 'T::max_value()' if the range
+    /// is empty.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn min(&self, range: Range<usize>) -> T {
+        self.check_range(&range);
+        self.fold(
+            0,
+            &self.nodes[0],
+            range.start,
+            range.end,
+            T::max_value(),
+            &|acc, node| min(acc, node.min.0),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BeatsSegmentTree;
+    use crate::test_util::Lcg;
+
+    #[test]
+    fn it_matches_naive_implementation() {
+        let mut rng = Lcg::new(7);
+        let n = 41;
+        let mut xs: Vec<i64> = (0..n).map(|_| rng.next(200) as i64 - 100).collect();
+        let mut t = BeatsSegmentTree::from_slice(&xs);
+        for _ in 0..2000 {
+            let l = rng.next(n + 1);
+            let r = l + rng.next(n + 1 - l);
+            let x = rng.next(200) as i64 - 100;
+            match rng.next(6) {
+                0 => {
+                    t.chmin(l..r, x);
+                    xs[l..r].iter_mut().for_each(|y| *y = (*y).min(x));
+                }
+                1 => {
+                    t.chmax(l..r, x);
+                    xs[l..r].iter_mut().for_each(|y| *y = (*y).max(x));
+                }
+                2 => {
+                    t.add(l..r, x / 10);
+                    xs[l..r].iter_mut().for_each(|y| *y += x / 10);
+                }
+                3 => assert_eq!(t.sum(l..r), xs[l..r].iter().sum::<i64>()),
+                4 => assert_eq!(
+                    t.max(l..r),
+                    xs[l..r].iter().cloned().max().unwrap_or(i64::MIN)
+                ),
+                _ => assert_eq!(
+                    t.min(l..r),
+                    xs[l..r].iter().cloned().min().unwrap_or(i64::MAX)
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn it_clamps_capacities() {
+        let mut t = BeatsSegmentTree::<u32>::from_slice(&[5, 12, 7, 30, 2]);
+        t.chmin(0..5, 10);
+        assert_eq!(t.sum(0..5), 34);
+        t.chmax(1..4, 8);
+        assert_eq!(t.sum(0..5), 35);
+        t.add(3..5, 4);
+        assert_eq!(t.max(0..5), 14);
+        assert_eq!(t.min(0..5), 5);
+        assert_eq!(t.sum(2..4), 22);
+    }
+}
@@ -0,0 +1,349 @@
+//! A compact segment tree which is updated and queried bottom-up without
+//! recursion.
+
+use super::SegmentTreeSpec;
+use std::iter::FromIterator;
+use std::ops::Range;
+
+/// A segment tree storing only '2n' values and no bounds. The leaves are stored
+/// at '[n,2n)' and vertex 'i' has children '2i' and '2i + 1'. Index 0 is
+/// unused.
+pub struct IterativeSegmentTree<Spec: SegmentTreeSpec> {
+    /// The specification defining the default value and how to combine values.
+    spec: Spec,
+    /// The number of values the tree is built over.
+    n: usize,
+    /// The values of each respective vertex in the tree.
+    values: Vec<Spec::T>,
+}
+
+impl<Spec: SegmentTreeSpec + Default> IterativeSegmentTree<Spec> {
+    /// Create a segment tree over 'n' values, each set to 'Spec::default()'.
+    ///
+    /// Time complexity: O(n)
+    pub fn new(n: usize) -> Self {
+        IterativeSegmentTree::with_spec(Default::default(), n)
+    }
+
+    /// Create a segment tree over the values in 'xs'.
+    ///
+    /// Time complexity: O(n)
+    pub fn from_slice(xs: &[Spec::T]) -> Self {
+        IterativeSegmentTree::from_slice_with_spec(Default::default(), xs)
+    }
+}
+
+impl<Spec: SegmentTreeSpec> IterativeSegmentTree<Spec> {
+    /// Create a segment tree over 'n' values using specification 'spec'. Each
+    /// value is set to 'spec.default()'.
+    ///
+    /// Time complexity: O(n)
+    pub fn with_spec(spec: Spec, n: usize) -> Self {
+        IterativeSegmentTree {
+            values: vec![spec.default(); 2 * n],
+            spec,
+            n,
+        }
+    }
+
+    /// Create a segment tree over the values in 'xs' using specification
+    /// 'spec'.
+    ///
+    /// Time complexity: O(n)
+    pub fn from_slice_with_spec(spec: Spec, xs: &[Spec::T]) -> Self {
+        let n = xs.len();
+        let mut t = IterativeSegmentTree::with_spec(spec, n);
+        t.values[n..].clone_from_slice(xs);
+        for i in (1..n).rev() {
+            t.pull(i);
+        }
+        t
+    }
+
+    /// The specification the segment tree was created with.
+    pub fn spec(&self) -> &Spec {
+        &self.spec
+    }
+
+    /// The number of values the segment tree is built over.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    /// True if the segment tree is built over zero values, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Recompute the value of vertex 'i' from its children.
+    fn pull(&mut self, i: usize) {
+        self.values[i] = self
+            .spec
+            .combine(self.values[2 * i].clone(), self.values[2 * i + 1].clone());
+    }
+
+    /// Set the value at index 'i' to 'x'.
+    ///
+    /// Panics if 'i' is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn update(&mut self, i: usize, x: Spec::T) {
+        assert!(i < self.n, "index {} out of bounds", i);
+        let mut i = i + self.n;
+        self.values[i] = x;
+        while i > 1 {
+            i /= 2;
+            self.pull(i);
+        }
+    }
+
+    /// Combine the values in the range '[l,r)'. Returns 'spec.default()' if
+    /// the range is empty.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn query(&self, range: Range<usize>) -> Spec::T {
+        let Range { start, end } = range;
+        assert!(
+            start <= end && end <= self.n,
+            "range {}..{} out of bounds",
+            start,
+            end
+        );
+        // Values to the left and right of the range are accumulated separately
+        // to preserve the order in which they are combined.
+        let mut acc_l = self.spec.default();
+        let mut acc_r = self.spec.default();
+        let mut l = start + self.n;
+        let mut r = end + self.n;
+        while l < r {
+            if l % 2 == 1 {
+                acc_l = self.spec.combine(acc_l, self.values[l].clone());
+                l += 1;
+            }
+            if r % 2 == 1 {
+                r -= 1;
+                acc_r = self.spec.combine(self.values[r].clone(), acc_r);
+            }
+            l /= 2;
+            r /= 2;
+        }
+        self.spec.combine(acc_l, acc_r)
+    }
+
+    /// Find the largest 'r' such that 'f' holds for the combined value of
+    /// '[l,r)'. The predicate must hold for 'spec.default()' and be monotone:
+    /// once it fails for '[l,r)' it fails for every larger 'r'.
+    ///
+    /// Panics if 'l' is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn max_right<F: Fn(&Spec::T) -> bool>(&self, l: usize, f: F) -> usize {
+        assert!(l <= self.n, "index {} out of bounds", l);
+        // Visit the vertices covering '[l,n)' from left to right, as in
+        // 'query'. Those on the right side are found in reverse order.
+        let mut acc = self.spec.default();
+        let mut right = Vec::new();
+        let mut l = l + self.n;
+        let mut r = 2 * self.n;
+        while l < r {
+            if l % 2 == 1 {
+                if let Some(i) = self.descend_right(l, &f, &mut acc) {
+                    return i;
+                }
+                l += 1;
+            }
+            if r % 2 == 1 {
+                r -= 1;
+                right.push(r);
+            }
+            l /= 2;
+            r /= 2;
+        }
+        for &i in right.iter().rev() {
+            if let Some(i) = self.descend_right(i, &f, &mut acc) {
+                return i;
+            }
+        }
+        self.n
+    }
+
+    /// Extend 'acc' with the values covered by vertex 'i' while 'f' holds.
+    /// Returns the index of the first value which makes 'f' fail.
+    fn descend_right<F: Fn(&Spec::T) -> bool>(
+        &self,
+        i: usize,
+        f: &F,
+        acc: &mut Spec::T,
+    ) -> Option<usize> {
+        let x = self.spec.combine(acc.clone(), self.values[i].clone());
+        if f(&x) {
+            *acc = x;
+            return None;
+        }
+        let mut i = i;
+        while i < self.n {
+            i *= 2;
+            let x = self.spec.combine(acc.clone(), self.values[i].clone());
+            if f(&x) {
+                *acc = x;
+                i += 1;
+            }
+        }
+        Some(i - self.n)
+    }
+
+    /// Find the smallest 'l' such that 'f' holds for the combined value of
+    /// '[l,r)'. The predicate must hold for 'spec.default()' and be monotone:
+    /// once it fails for '[l,r)' it fails for every smaller 'l'.
+    ///
+    /// Panics if 'r' is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn min_left<F: Fn(&Spec::T) -> bool>(&self, r: usize, f: F) -> usize {
+        assert!(r <= self.n, "index {} out of bounds", r);
+        // Visit the vertices covering '[0,r)' from right to left. Those on the
+        // left side are found in reverse order.
+        let mut acc = self.spec.default();
+        let mut left = Vec::new();
+        let mut l = self.n;
+        let mut r = r + self.n;
+        while l < r {
+            if l % 2 == 1 {
+                left.push(l);
+                l += 1;
+            }
+            if r % 2 == 1 {
+                r -= 1;
+                if let Some(i) = self.descend_left(r, &f, &mut acc) {
+                    return i;
+                }
+            }
+            l /= 2;
+            r /= 2;
+        }
+        for &i in left.iter().rev() {
+            if let Some(i) = self.descend_left(i, &f, &mut acc) {
+                return i;
+            }
+        }
+        0
+    }
+
+    /// Extend 'acc' with the values covered by vertex 'i', from right to
+    /// left, while 'f' holds. Returns the index after the first value which
+    /// makes 'f' fail.
+    fn descend_left<F: Fn(&Spec::T) -> bool>(
+        &self,
+        i: usize,
+        f: &F,
+        acc: &mut Spec::T,
+    ) -> Option<usize> {
+        let x = self.spec.combine(self.values[i].clone(), acc.clone());
+        if f(&x) {
+            *acc = x;
+            return None;
+        }
+        let mut i = i;
+        while i < self.n {
+            i = 2 * i + 1;
+            let x = self.spec.combine(self.values[i].clone(), acc.clone());
+            if f(&x) {
+                *acc = x;
+                i -= 1;
+            }
+        }
+        Some(i + 1 - self.n)
+    }
+}
+
+impl<Spec: SegmentTreeSpec + Default> FromIterator<Spec::T> for IterativeSegmentTree<Spec> {
+    fn from_iter<I: IntoIterator<Item = Spec::T>>(iter: I) -> Self {
+        let xs: Vec<Spec::T> = iter.into_iter().collect();
+        IterativeSegmentTree::from_slice(&xs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IterativeSegmentTree;
+    use crate::segment_tree::{FnSpec, MaxSpec, MinSpec, SumSpec};
+
+    #[test]
+    fn it_answers_queries() {
+        let xs = [4, 2, 7, 1, 9, 3, 3];
+        let mut t: IterativeSegmentTree<MinSpec<i32>> = xs.iter().cloned().collect();
+        for l in 0..xs.len() {
+            for r in l + 1..=xs.len() {
+                assert_eq!(t.query(l..r), *xs[l..r].iter().min().unwrap());
+            }
+        }
+        t.update(4, 0);
+        assert_eq!(t.query(0..7), 0);
+        assert_eq!(t.query(5..7), 3);
+        assert_eq!(t.query(2..2), i32::MAX);
+    }
+
+    #[test]
+    fn it_preserves_combination_order() {
+        let xs: Vec<String> = "segment".chars().map(|c| c.to_string()).collect();
+        let spec = FnSpec::new(String::new(), |a: String, b: String| a + &b);
+        let mut t = IterativeSegmentTree::from_slice_with_spec(spec, &xs);
+        for l in 0..xs.len() {
+            for r in l..=xs.len() {
+                assert_eq!(t.query(l..r), xs[l..r].concat());
+            }
+        }
+        t.update(0, "f".to_string());
+        assert_eq!(t.query(0..4), "fegm");
+    }
+
+    #[test]
+    fn it_descends_to_max_right() {
+        let xs = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        // Sizes which are not powers of two put the leaves at different depths.
+        for n in 1..=xs.len() {
+            let t = IterativeSegmentTree::<SumSpec<i32>>::from_slice(&xs[..n]);
+            for l in 0..=n {
+                for k in 0..50 {
+                    let mut r = l;
+                    while r < n && xs[l..=r].iter().sum::<i32>() <= k {
+                        r += 1;
+                    }
+                    assert_eq!(t.max_right(l, |&s| s <= k), r);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_descends_to_min_left() {
+        let xs = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5];
+        for n in 1..=xs.len() {
+            let t = IterativeSegmentTree::<MaxSpec<i32>>::from_slice(&xs[..n]);
+            for r in 0..=n {
+                for k in 0..10 {
+                    let mut l = r;
+                    while l > 0 && xs[l - 1] <= k {
+                        l -= 1;
+                    }
+                    assert_eq!(t.min_left(r, |&m| m <= k), l);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_handles_empty_tree() {
+        let t = IterativeSegmentTree::<MinSpec<i32>>::new(0);
+        assert!(t.is_empty());
+        assert_eq!(t.query(0..0), i32::MAX);
+        assert_eq!(t.max_right(0, |&m| m > 0), 0);
+        assert_eq!(t.min_left(0, |&m| m > 0), 0);
+    }
+}
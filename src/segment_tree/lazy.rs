@@ -0,0 +1,540 @@
+//! Segment trees supporting updates over whole ranges by lazily propagating
+//! updates down the tree.
+
+use super::{vertex_bounds, SegmentTreeSpec};
+use num::{Bounded, FromPrimitive, Num};
+use std::cmp::{max, min};
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::Range;
+
+/// A lazy segment tree is defined by a segment tree specification together
+/// with the updates which can be applied to its elements.
+///
+/// Applying an update to a combined value must be equivalent to applying it to
+/// each element separately and then combining them.
+pub trait LazySegmentTreeSpec: SegmentTreeSpec {
+    type U: Clone;
+    /// The update which leaves every element unchanged.
+    fn identity(&self) -> Self::U;
+    /// Compose two updates such that applying the result is equivalent to first
+    /// applying 'f' and then 'g'.
+    fn compose(&self, f: Self::U, g: Self::U) -> Self::U;
+    /// Apply update 'f' to 'x', the combined value of a segment of 'len'
+    /// elements.
+    fn apply(&self, f: Self::U, x: Self::T, len: usize) -> Self::T;
+}
+
+/// Lazy segment tree specification for adding to ranges and computing range
+/// sums.
+pub struct AddSumSpec<T>(PhantomData<T>);
+
+impl<T> Default for AddSumSpec<T> {
+    fn default() -> Self {
+        AddSumSpec(PhantomData)
+    }
+}
+
+impl<T: Num + FromPrimitive + Clone> SegmentTreeSpec for AddSumSpec<T> {
+    type T = T;
+    fn default(&self) -> T {
+        T::zero()
+    }
+    fn combine(&self, a: T, b: T) -> T {
+        a + b
+    }
+}
+
+impl<T: Num + FromPrimitive + Clone> LazySegmentTreeSpec for AddSumSpec<T> {
+    type U = T;
+    fn identity(&self) -> T {
+        T::zero()
+    }
+    fn compose(&self, f: T, g: T) -> T {
+        f + g
+    }
+    fn apply(&self, f: T, x: T, len: usize) -> T {
+        x + f * T::from_usize(len).expect("segment length not representable")
+    }
+}
+
+/// Lazy segment tree specification for adding to ranges and computing range
+/// minimums. Values are 'Some(x)' while 'None' is the minimum of an empty
+/// range, so a tree created with 'new' holds no values to add to.
+pub struct AddMinSpec<T>(PhantomData<T>);
+
+impl<T> Default for AddMinSpec<T> {
+    fn default() -> Self {
+        AddMinSpec(PhantomData)
+    }
+}
+
+impl<T: Num + Ord + Clone> SegmentTreeSpec for AddMinSpec<T> {
+    type T = Option<T>;
+    fn default(&self) -> Option<T> {
+        None
+    }
+    fn combine(&self, a: Option<T>, b: Option<T>) -> Option<T> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(min(a, b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+impl<T: Num + Ord + Clone> LazySegmentTreeSpec for AddMinSpec<T> {
+    type U = T;
+    fn identity(&self) -> T {
+        T::zero()
+    }
+    fn compose(&self, f: T, g: T) -> T {
+        f + g
+    }
+    fn apply(&self, f: T, x: Option<T>, _len: usize) -> Option<T> {
+        x.map(|x| x + f)
+    }
+}
+
+/// Lazy segment tree specification for adding to ranges and computing range
+/// maximums. Values are 'Some(x)' while 'None' is the maximum of an empty
+/// range, so a tree created with 'new' holds no values to add to.
+pub struct AddMaxSpec<T>(PhantomData<T>);
+
+impl<T> Default for AddMaxSpec<T> {
+    fn default() -> Self {
+        AddMaxSpec(PhantomData)
+    }
+}
+
+impl<T: Num + Ord + Clone> SegmentTreeSpec for AddMaxSpec<T> {
+    type T = Option<T>;
+    fn default(&self) -> Option<T> {
+        None
+    }
+    fn combine(&self, a: Option<T>, b: Option<T>) -> Option<T> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(max(a, b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+impl<T: Num + Ord + Clone> LazySegmentTreeSpec for AddMaxSpec<T> {
+    type U = T;
+    fn identity(&self) -> T {
+        T::zero()
+    }
+    fn compose(&self, f: T, g: T) -> T {
+        f + g
+    }
+    fn apply(&self, f: T, x: Option<T>, _len: usize) -> Option<T> {
+        x.map(|x| x + f)
+    }
+}
+
+/// Lazy segment tree specification for assigning to ranges and computing range
+/// sums.
+pub struct AssignSumSpec<T>(PhantomData<T>);
+
+impl<T> Default for AssignSumSpec<T> {
+    fn default() -> Self {
+        AssignSumSpec(PhantomData)
+    }
+}
+
+impl<T: Num + FromPrimitive + Clone> SegmentTreeSpec for AssignSumSpec<T> {
+    type T = T;
+    fn default(&self) -> T {
+        T::zero()
+    }
+    fn combine(&self, a: T, b: T) -> T {
+        a + b
+    }
+}
+
+impl<T: Num + FromPrimitive + Clone> LazySegmentTreeSpec for AssignSumSpec<T> {
+    type U = Option<T>;
+    fn identity(&self) -> Option<T> {
+        None
+    }
+    fn compose(&self, f: Option<T>, g: Option<T>) -> Option<T> {
+        g.or(f)
+    }
+    fn apply(&self, f: Option<T>, x: T, len: usize) -> T {
+        match f {
+            None => x,
+            Some(v) => v * T::from_usize(len).expect("segment length not representable"),
+        }
+    }
+}
+
+/// Lazy segment tree specification for assigning to ranges and computing range
+/// minimums.
+pub struct AssignMinSpec<T>(PhantomData<T>);
+
+impl<T> Default for AssignMinSpec<T> {
+    fn default() -> Self {
+        AssignMinSpec(PhantomData)
+    }
+}
+
+impl<T: Bounded + Ord + Clone> SegmentTreeSpec for AssignMinSpec<T> {
+    type T = T;
+    fn default(&self) -> T {
+        T::max_value()
+    }
+    fn combine(&self, a: T, b: T) -> T {
+        min(a, b)
+    }
+}
+
+impl<T: Bounded + Ord + Clone> LazySegmentTreeSpec for AssignMinSpec<T> {
+    type U = Option<T>;
+    fn identity(&self) -> Option<T> {
+        None
+    }
+    fn compose(&self, f: Option<T>, g: Option<T>) -> Option<T> {
+        g.or(f)
+    }
+    fn apply(&self, f: Option<T>, x: T, _len: usize) -> T {
+        f.unwrap_or(x)
+    }
+}
+
+/// Lazy segment tree specification for assigning to ranges and computing range
+/// maximums.
+pub struct AssignMaxSpec<T>(PhantomData<T>);
+
+impl<T> Default for AssignMaxSpec<T> {
+    fn default() -> Self {
+        AssignMaxSpec(PhantomData)
+    }
+}
+
+impl<T: Bounded + Ord + Clone> SegmentTreeSpec for AssignMaxSpec<T> {
+    type T = T;
+    fn default(&self) -> T {
+        T::min_value()
+    }
+    fn combine(&self, a: T, b: T) -> T {
+        max(a, b)
+    }
+}
+
+impl<T: Bounded + Ord + Clone> LazySegmentTreeSpec for AssignMaxSpec<T> {
+    type U = Option<T>;
+    fn identity(&self) -> Option<T> {
+        None
+    }
+    fn compose(&self, f: Option<T>, g: Option<T>) -> Option<T> {
+        g.or(f)
+    }
+    fn apply(&self, f: Option<T>, x: T, _len: usize) -> T {
+        f.unwrap_or(x)
+    }
+}
+
+/// A segment tree which supports applying updates to and querying sub-ranges
+/// over its elements.
+pub struct LazySegmentTree<Spec: LazySegmentTreeSpec> {
+    /// The specification defining the values, updates and how they interact.
+    spec: Spec,
+    /// The values of each respective vertex in the tree. Updates which are
+    /// still pending in an ancestor have not been applied.
+    values: Vec<Spec::T>,
+    /// For each vertex the update which has not yet been pushed down to its
+    /// children.
+    pending: Vec<Spec::U>,
+    /// For each vertex in the tree their bound '[l,r)' represents the range it
+    /// covers.
+    bounds: Vec<(usize, usize)>,
+}
+
+impl<Spec: LazySegmentTreeSpec + Default> LazySegmentTree<Spec> {
+    /// Create a lazy segment tree over 'n' values, each set to
+    /// 'Spec::default()'.
+    ///
+    /// Time complexity: O(n)
+    pub fn new(n: usize) -> Self {
+        LazySegmentTree::with_spec(Default::default(), n)
+    }
+
+    /// Create a lazy segment tree over the values in 'xs'.
+    ///
+    /// Time complexity: O(n)
+    pub fn from_slice(xs: &[Spec::T]) -> Self {
+        LazySegmentTree::from_slice_with_spec(Default::default(), xs)
+    }
+}
+
+impl<Spec: LazySegmentTreeSpec> LazySegmentTree<Spec> {
+    /// Create a lazy segment tree over 'n' values using specification 'spec'.
+    /// Each value is set to 'spec.default()'.
+    ///
+    /// Time complexity: O(n)
+    pub fn with_spec(spec: Spec, n: usize) -> Self {
+        let bounds = vertex_bounds(n);
+        LazySegmentTree {
+            values: vec![spec.default(); bounds.len()],
+            pending: vec![spec.identity(); bounds.len()],
+            spec,
+            bounds,
+        }
+    }
+
+    /// Create a lazy segment tree over the values in 'xs' using specification
+    /// 'spec'.
+    ///
+    /// Time complexity: O(n)
+    pub fn from_slice_with_spec(spec: Spec, xs: &[Spec::T]) -> Self {
+        let mut t = LazySegmentTree::with_spec(spec, xs.len());
+        for idx in (0..t.values.len()).rev() {
+            let (l, r) = t.bounds[idx];
+            if r - l == 1 {
+                t.values[idx] = xs[l].clone();
+            } else if r - l > 1 {
+                t.pull(idx);
+            }
+        }
+        t
+    }
+
+    /// The specification the segment tree was created with.
+    pub fn spec(&self) -> &Spec {
+        &self.spec
+    }
+
+    /// The number of values the segment tree is built over.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.bounds[0].1
+    }
+
+    /// True if the segment tree is built over zero values, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Recompute the value of vertex 'idx' from its children.
+    fn pull(&mut self, idx: usize) {
+        self.values[idx] = self.spec.combine(
+            self.values[idx * 2 + 1].clone(),
+            self.values[idx * 2 + 2].clone(),
+        );
+    }
+
+    /// Apply update 'f' to the whole segment covered by vertex 'idx'.
+    fn apply_at(&mut self, idx: usize, f: Spec::U) {
+        let (l, r) = self.bounds[idx];
+        let x = mem::replace(&mut self.values[idx], self.spec.default());
+        self.values[idx] = self.spec.apply(f.clone(), x, r - l);
+        if r - l > 1 {
+            let g = mem::replace(&mut self.pending[idx], self.spec.identity());
+            self.pending[idx] = self.spec.compose(g, f);
+        }
+    }
+
+    /// Push the pending update of vertex 'idx' down to its children.
+    fn push(&mut self, idx: usize) {
+        let f = mem::replace(&mut self.pending[idx], self.spec.identity());
+        self.apply_at(idx * 2 + 1, f.clone());
+        self.apply_at(idx * 2 + 2, f);
+    }
+
+    /// Apply update 'f' to every value in the range '[l,r)'.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn update(&mut self, range: Range<usize>, f: Spec::U) {
+        let Range { start, end } = range;
+        assert!(
+            start <= end && end <= self.len(),
+            "range {}..{} out of bounds",
+            start,
+            end
+        );
+        self.update_rec(0, start, end, &f);
+    }
+
+    /// Apply update 'f' to the values in '[l,r)' which are covered by vertex
+    /// 'idx'.
+    fn update_rec(&mut self, idx: usize, l: usize, r: usize, f: &Spec::U) {
+        let (vl, vr) = self.bounds[idx];
+        if r <= vl || vr <= l {
+            return;
+        }
+        if l <= vl && vr <= r {
+            self.apply_at(idx, f.clone());
+        } else {
+            self.push(idx);
+            self.update_rec(idx * 2 + 1, l, r, f);
+            self.update_rec(idx * 2 + 2, l, r, f);
+            self.pull(idx);
+        }
+    }
+
+    /// Combine the values in the range '[l,r)'. Returns 'spec.default()' if
+    /// the range is empty.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn query(&self, range: Range<usize>) -> Spec::T {
+        let Range { start, end } = range;
+        assert!(
+            start <= end && end <= self.len(),
+            "range {}..{} out of bounds",
+            start,
+            end
+        );
+        self.query_rec(0, start, end)
+    }
+
+    /// Combine the values in '[l,r)' which are covered by vertex 'idx'.
+    fn query_rec(&self, idx: usize, l: usize, r: usize) -> Spec::T {
+        let (vl, vr) = self.bounds[idx];
+        if r <= vl || vr <= l || r <= l {
+            self.spec.default()
+        } else if l <= vl && vr <= r {
+            self.values[idx].clone()
+        } else {
+            // Rather than pushing the pending update down, apply it to the
+            // combined value of the part of the segment being queried.
+            let x = self.spec.combine(
+                self.query_rec(idx * 2 + 1, l, r),
+                self.query_rec(idx * 2 + 2, l, r),
+            );
+            let len = min(r, vr) - max(l, vl);
+            self.spec.apply(self.pending[idx].clone(), x, len)
+        }
+    }
+}
+
+impl<Spec: LazySegmentTreeSpec + Default> FromIterator<Spec::T> for LazySegmentTree<Spec> {
+    fn from_iter<I: IntoIterator<Item = Spec::T>>(iter: I) -> Self {
+        let xs: Vec<Spec::T> = iter.into_iter().collect();
+        LazySegmentTree::from_slice(&xs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AddMaxSpec, AddMinSpec, AddSumSpec};
+    use super::{AssignMaxSpec, AssignMinSpec, AssignSumSpec};
+    use super::{LazySegmentTree, LazySegmentTreeSpec};
+    use crate::test_util::Lcg;
+    use std::fmt::Debug;
+
+    /// Apply random updates and queries to a lazy segment tree and compare the
+    /// answers against a naive implementation.
+    fn check_against_naive<Spec, F, G>(mut random_update: F, mut naive_apply: G)
+    where
+        Spec: LazySegmentTreeSpec + Default,
+        Spec::T: From<i64> + PartialEq + Debug,
+        F: FnMut(&mut Lcg) -> Spec::U,
+        G: FnMut(&Spec::U, &mut i64),
+    {
+        let mut rng = Lcg::new(42);
+        let n = 37;
+        let mut xs: Vec<i64> = (0..n).map(|_| rng.next(100) as i64).collect();
+        let mut t: LazySegmentTree<Spec> = xs.iter().map(|&x| x.into()).collect();
+        for _ in 0..500 {
+            let l = rng.next(n + 1);
+            let r = l + rng.next(n + 1 - l);
+            if rng.next(2) == 0 {
+                let f = random_update(&mut rng);
+                xs[l..r].iter_mut().for_each(|x| naive_apply(&f, x));
+                t.update(l..r, f);
+            } else {
+                let spec = t.spec();
+                let expected = xs[l..r]
+                    .iter()
+                    .fold(spec.default(), |acc, &x| spec.combine(acc, x.into()));
+                assert_eq!(t.query(l..r), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn it_adds_and_sums() {
+        check_against_naive::<AddSumSpec<i64>, _, _>(
+            |rng| rng.next(21) as i64 - 10,
+            |&f, x| *x += f,
+        );
+    }
+
+    #[test]
+    fn it_adds_and_takes_min() {
+        check_against_naive::<AddMinSpec<i64>, _, _>(
+            |rng| rng.next(21) as i64 - 10,
+            |&f, x| *x += f,
+        );
+    }
+
+    #[test]
+    fn it_adds_and_takes_max() {
+        check_against_naive::<AddMaxSpec<i64>, _, _>(
+            |rng| rng.next(21) as i64 - 10,
+            |&f, x| *x += f,
+        );
+    }
+
+    #[test]
+    fn it_assigns_and_sums() {
+        check_against_naive::<AssignSumSpec<i64>, _, _>(
+            |rng| Some(rng.next(100) as i64),
+            |f, x| *x = f.unwrap(),
+        );
+    }
+
+    #[test]
+    fn it_assigns_and_takes_min() {
+        check_against_naive::<AssignMinSpec<i64>, _, _>(
+            |rng| Some(rng.next(100) as i64),
+            |f, x| *x = f.unwrap(),
+        );
+    }
+
+    #[test]
+    fn it_assigns_and_takes_max() {
+        check_against_naive::<AssignMaxSpec<i64>, _, _>(
+            |rng| Some(rng.next(100) as i64),
+            |f, x| *x = f.unwrap(),
+        );
+    }
+
+    #[test]
+    fn it_adds_to_zero_initialized_tree() {
+        let mut t = LazySegmentTree::<AddSumSpec<i32>>::new(10);
+        t.update(2..7, 3);
+        t.update(0..4, 1);
+        assert_eq!(t.query(0..10), 19);
+        assert_eq!(t.query(3..5), 7);
+        assert_eq!(t.query(7..10), 0);
+    }
+
+    #[test]
+    fn it_adds_to_extreme_min_max_values() {
+        let xs = [Some(i32::MAX), Some(5), Some(i32::MAX)];
+        let mut t = LazySegmentTree::<AddMinSpec<i32>>::from_slice(&xs);
+        t.update(0..3, -10);
+        assert_eq!(t.query(0..1), Some(i32::MAX - 10));
+        assert_eq!(t.query(0..3), Some(-5));
+        assert_eq!(t.query(1..1), None);
+
+        let xs = [Some(i32::MIN), Some(-5)];
+        let mut t = LazySegmentTree::<AddMaxSpec<i32>>::from_slice(&xs);
+        t.update(0..2, 10);
+        assert_eq!(t.query(0..1), Some(i32::MIN + 10));
+        assert_eq!(t.query(0..2), Some(5));
+
+        let mut t = LazySegmentTree::<AddMinSpec<i32>>::new(4);
+        t.update(0..2, 1);
+        assert_eq!(t.query(0..4), None);
+    }
+}
@@ -0,0 +1,460 @@
+//! Li Chao trees for inserting lines and querying the minimum or maximum of
+//! all inserted lines at a point.
+
+use super::sparse::midpoint;
+use super::{vertex_bounds, SegmentTreeSpec};
+use num::{Num, One, PrimInt};
+use std::ops::Range;
+
+/// The line 'y = a * x + b'.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Line<T> {
+    pub a: T,
+    pub b: T,
+}
+
+impl<T: Num + Copy> Line<T> {
+    /// Create the line 'y = a * x + b'.
+    pub fn new(a: T, b: T) -> Self {
+        Line { a, b }
+    }
+
+    /// The value of the line at 'x'.
+    pub fn eval(&self, x: T) -> T {
+        self.a * x + self.b
+    }
+}
+
+/// True if 'spec' prefers 'y1' over 'y2'.
+fn prefers<Spec>(spec: &Spec, y1: Spec::T, y2: Spec::T) -> bool
+where
+    Spec: SegmentTreeSpec,
+    Spec::T: PartialEq,
+{
+    spec.combine(y1.clone(), y2) == y1
+}
+
+/// A Li Chao tree over a fixed set of x-coordinates. The specification decides
+/// which value is kept, 'MinSpec' answers minimum queries and 'MaxSpec'
+/// answers maximum queries.
+pub struct LiChaoTree<Spec: SegmentTreeSpec> {
+    /// The specification choosing between the values of two lines.
+    spec: Spec,
+    /// The sorted x-coordinates which can be queried.
+    xs: Vec<Spec::T>,
+    /// The line stored at each respective vertex in the tree.
+    lines: Vec<Option<Line<Spec::T>>>,
+    /// For each vertex in the tree their bound '[l,r)' represents the range of
+    /// x-coordinate indices it covers.
+    bounds: Vec<(usize, usize)>,
+}
+
+impl<Spec: SegmentTreeSpec + Default> LiChaoTree<Spec>
+where
+    Spec::T: Num + Copy + Ord,
+{
+    /// Create an empty Li Chao tree which can be queried at the x-coordinates
+    /// in 'xs'.
+    ///
+    /// Time complexity: O(n log n)
+    pub fn new(xs: Vec<Spec::T>) -> Self {
+        LiChaoTree::with_spec(Default::default(), xs)
+    }
+}
+
+impl<Spec: SegmentTreeSpec> LiChaoTree<Spec>
+where
+    Spec::T: Num + Copy + Ord,
+{
+    /// Create an empty Li Chao tree which can be queried at the x-coordinates
+    /// in 'xs' using specification 'spec'.
+    ///
+    /// Time complexity: O(n log n)
+    pub fn with_spec(spec: Spec, mut xs: Vec<Spec::T>) -> Self {
+        xs.sort_unstable();
+        xs.dedup();
+        let bounds = vertex_bounds(xs.len());
+        LiChaoTree {
+            spec,
+            xs,
+            lines: vec![None; bounds.len()],
+            bounds,
+        }
+    }
+
+    /// Insert 'line' over the whole domain.
+    ///
+    /// Time complexity: O(log n)
+    pub fn insert(&mut self, line: Line<Spec::T>) {
+        self.insert_rec(0, line);
+    }
+
+    /// Insert 'line' into the subtree of vertex 'idx'.
+    fn insert_rec(&mut self, mut idx: usize, mut line: Line<Spec::T>) {
+        loop {
+            let (l, r) = self.bounds[idx];
+            if r == l {
+                return;
+            }
+            let cur = match self.lines[idx] {
+                None => {
+                    self.lines[idx] = Some(line);
+                    return;
+                }
+                Some(cur) => cur,
+            };
+            // Keep the line which is preferred at the start of the right half,
+            // the other line can only be preferred within one of the halves.
+            let m = l + (r - l) / 2;
+            let xm = self.xs[m];
+            if prefers(&self.spec, line.eval(xm), cur.eval(xm)) {
+                self.lines[idx] = Some(line);
+                line = cur;
+            }
+            if r - l == 1 {
+                return;
+            }
+            let xl = self.xs[l];
+            let best = self.lines[idx].unwrap();
+            idx = if prefers(&self.spec, line.eval(xl), best.eval(xl))
+                && line.eval(xl) != best.eval(xl)
+            {
+                idx * 2 + 1
+            } else {
+                idx * 2 + 2
+            };
+        }
+    }
+
+    /// Insert 'line' for the x-coordinates in 'range' only.
+    ///
+    /// Time complexity: O(log^2 n)
+    pub fn insert_segment(&mut self, line: Line<Spec::T>, range: Range<Spec::T>) {
+        let l = self.xs.partition_point(|&x| x < range.start);
+        let r = self.xs.partition_point(|&x| x < range.end);
+        self.insert_segment_rec(0, line, l, r);
+    }
+
+    /// Insert 'line' for the x-coordinate indices in '[l,r)' covered by vertex
+    /// 'idx'.
+    fn insert_segment_rec(&mut self, idx: usize, line: Line<Spec::T>, l: usize, r: usize) {
+        let (vl, vr) = self.bounds[idx];
+        if r <= vl || vr <= l || r <= l {
+            return;
+        }
+        if l <= vl && vr <= r {
+            self.insert_rec(idx, line);
+        } else {
+            self.insert_segment_rec(idx * 2 + 1, line, l, r);
+            self.insert_segment_rec(idx * 2 + 2, line, l, r);
+        }
+    }
+
+    /// The preferred value of all lines covering 'x', or 'spec.default()' if
+    /// there are none.
+    ///
+    /// Panics if 'x' is not one of the x-coordinates of the tree.
+    ///
+    /// Time complexity: O(log n)
+    pub fn query(&self, x: Spec::T) -> Spec::T {
+        let i = self
+            .xs
+            .binary_search(&x)
+            .expect("x-coordinate not in domain");
+        let mut acc = self.spec.default();
+        let mut idx = 0;
+        loop {
+            if let Some(line) = self.lines[idx] {
+                acc = self.spec.combine(acc, line.eval(x));
+            }
+            let (l, r) = self.bounds[idx];
+            if r - l == 1 {
+                return acc;
+            }
+            let m = l + (r - l) / 2;
+            idx = if i < m { idx * 2 + 1 } else { idx * 2 + 2 };
+        }
+    }
+}
+
+/// A vertex of a sparse Li Chao tree.
+struct Node<T> {
+    line: Option<Line<T>>,
+    /// Left and right child of the vertex, if they have been allocated.
+    children: [Option<usize>; 2],
+}
+
+/// A Li Chao tree over the integer x-coordinates '[lo,hi)' which only
+/// allocates the vertices lines are inserted into. The specification decides
+/// which value is kept, 'MinSpec' answers minimum queries and 'MaxSpec'
+/// answers maximum queries.
+pub struct SparseLiChaoTree<Spec: SegmentTreeSpec> {
+    /// The specification choosing between the values of two lines.
+    spec: Spec,
+    /// The range of x-coordinates covered by the tree.
+    domain: Range<Spec::T>,
+    /// Allocated vertices. The root is always at index 0.
+    nodes: Vec<Node<Spec::T>>,
+}
+
+impl<Spec: SegmentTreeSpec + Default> SparseLiChaoTree<Spec>
+where
+    Spec::T: PrimInt,
+{
+    /// Create an empty Li Chao tree over the x-coordinates in 'domain'. The
+    /// width of the domain must be representable in 'Spec::T'.
+    ///
+    /// Time complexity: O(1)
+    pub fn new(domain: Range<Spec::T>) -> Self {
+        SparseLiChaoTree::with_spec(Default::default(), domain)
+    }
+}
+
+impl<Spec: SegmentTreeSpec> SparseLiChaoTree<Spec>
+where
+    Spec::T: PrimInt,
+{
+    /// Create an empty Li Chao tree over the x-coordinates in 'domain' using
+    /// specification 'spec'. The width of the domain must be representable in
+    /// 'Spec::T'.
+    ///
+    /// Time complexity: O(1)
+    pub fn with_spec(spec: Spec, domain: Range<Spec::T>) -> Self {
+        assert!(domain.start <= domain.end, "invalid domain");
+        SparseLiChaoTree {
+            spec,
+            domain,
+            nodes: vec![Node {
+                line: None,
+                children: [None, None],
+            }],
+        }
+    }
+
+    /// The range of x-coordinates covered by the tree.
+    pub fn domain(&self) -> Range<Spec::T> {
+        self.domain.clone()
+    }
+
+    /// The child 'side' of vertex 'idx', allocating it if necessary.
+    fn child(&mut self, idx: usize, side: usize) -> usize {
+        match self.nodes[idx].children[side] {
+            Some(c) => c,
+            None => {
+                self.nodes.push(Node {
+                    line: None,
+                    children: [None, None],
+                });
+                let c = self.nodes.len() - 1;
+                self.nodes[idx].children[side] = Some(c);
+                c
+            }
+        }
+    }
+
+    /// Insert 'line' over the whole domain.
+    ///
+    /// Time complexity: O(log(hi - lo))
+    pub fn insert(&mut self, line: Line<Spec::T>) {
+        self.insert_rec(0, self.domain.start, self.domain.end, line);
+    }
+
+    /// Insert 'line' into the subtree of vertex 'idx' with bound '[l,r)'.
+    fn insert_rec(
+        &mut self,
+        mut idx: usize,
+        mut l: Spec::T,
+        mut r: Spec::T,
+        mut line: Line<Spec::T>,
+    ) {
+        loop {
+            if r == l {
+                return;
+            }
+            let cur = match self.nodes[idx].line {
+                None => {
+                    self.nodes[idx].line = Some(line);
+                    return;
+                }
+                Some(cur) => cur,
+            };
+            // Keep the line which is preferred at the start of the right half,
+            // the other line can only be preferred within one of the halves.
+            let m = midpoint(l, r);
+            if prefers(&self.spec, line.eval(m), cur.eval(m)) {
+                self.nodes[idx].line = Some(line);
+                line = cur;
+            }
+            if r - l == Spec::T::one() {
+                return;
+            }
+            let best = self.nodes[idx].line.unwrap();
+            if prefers(&self.spec, line.eval(l), best.eval(l)) && line.eval(l) != best.eval(l) {
+                idx = self.child(idx, 0);
+                r = m;
+            } else {
+                idx = self.child(idx, 1);
+                l = m;
+            }
+        }
+    }
+
+    /// Insert 'line' for the x-coordinates in 'range' only.
+    ///
+    /// Panics if the range is not contained in the domain.
+    ///
+    /// Time complexity: O(log^2(hi - lo))
+    pub fn insert_segment(&mut self, line: Line<Spec::T>, range: Range<Spec::T>) {
+        assert!(
+            self.domain.start <= range.start
+                && range.start <= range.end
+                && range.end <= self.domain.end,
+            "range out of bounds"
+        );
+        let (lo, hi) = (self.domain.start, self.domain.end);
+        self.insert_segment_rec(0, lo, hi, line, range.start, range.end);
+    }
+
+    /// Insert 'line' for the x-coordinates in '[l,r)' covered by vertex 'idx'
+    /// with bound '[vl,vr)'.
+    fn insert_segment_rec(
+        &mut self,
+        idx: usize,
+        vl: Spec::T,
+        vr: Spec::T,
+        line: Line<Spec::T>,
+        l: Spec::T,
+        r: Spec::T,
+    ) {
+        if r <= vl || vr <= l || r <= l {
+            return;
+        }
+        if l <= vl && vr <= r {
+            self.insert_rec(idx, vl, vr, line);
+        } else {
+            // Only create the children the segment overlaps, otherwise every
+            // partially covered vertex would allocate an empty sibling.
+            let m = midpoint(vl, vr);
+            if l < m {
+                let left = self.child(idx, 0);
+                self.insert_segment_rec(left, vl, m, line, l, r);
+            }
+            if m < r {
+                let right = self.child(idx, 1);
+                self.insert_segment_rec(right, m, vr, line, l, r);
+            }
+        }
+    }
+
+    /// The preferred value of all lines covering 'x', or 'spec.default()' if
+    /// there are none.
+    ///
+    /// Panics if 'x' is outside the domain.
+    ///
+    /// Time complexity: O(log(hi - lo))
+    pub fn query(&self, x: Spec::T) -> Spec::T {
+        assert!(self.domain.contains(&x), "x-coordinate out of bounds");
+        let (mut l, mut r) = (self.domain.start, self.domain.end);
+        let mut acc = self.spec.default();
+        let mut idx = Some(0);
+        while let Some(i) = idx {
+            if let Some(line) = self.nodes[i].line {
+                acc = self.spec.combine(acc, line.eval(x));
+            }
+            let m = midpoint(l, r);
+            if x < m {
+                idx = self.nodes[i].children[0];
+                r = m;
+            } else {
+                idx = self.nodes[i].children[1];
+                l = m;
+            }
+        }
+        acc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LiChaoTree, Line, SparseLiChaoTree};
+    use crate::segment_tree::{MaxSpec, MinSpec};
+
+    /// Lines with a mix of slopes and intercepts, including duplicates and
+    /// parallel lines.
+    fn lines() -> Vec<Line<i64>> {
+        (0..30)
+            .map(|k| Line::new((k * 7) % 11 - 5, (k * 13) % 40 - 20))
+            .collect()
+    }
+
+    #[test]
+    fn it_finds_minimum_over_fixed_domain() {
+        let xs: Vec<i64> = (-20..20).map(|x| x * 3).collect();
+        let mut t = LiChaoTree::<MinSpec<i64>>::new(xs.clone());
+        let mut inserted = Vec::new();
+        for line in lines() {
+            t.insert(line);
+            inserted.push(line);
+            for &x in xs.iter() {
+                let expected = inserted.iter().map(|l| l.eval(x)).min().unwrap();
+                assert_eq!(t.query(x), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn it_finds_maximum_of_segments() {
+        let xs: Vec<i64> = (0..25).collect();
+        let mut t = LiChaoTree::<MaxSpec<i64>>::new(xs.clone());
+        let mut inserted = Vec::new();
+        for (k, line) in lines().into_iter().enumerate() {
+            let range = (k as i64 * 5) % 25..(k as i64 * 5) % 25 + 7;
+            t.insert_segment(line, range.clone());
+            inserted.push((line, range));
+            for &x in xs.iter() {
+                let expected = inserted
+                    .iter()
+                    .filter(|(_, range)| range.contains(&x))
+                    .map(|(l, _)| l.eval(x))
+                    .max()
+                    .unwrap_or(i64::MIN);
+                assert_eq!(t.query(x), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn it_finds_minimum_over_sparse_domain() {
+        let mut t = SparseLiChaoTree::<MinSpec<i64>>::new(-1_000_000_000..1_000_000_000);
+        let queries = [-1_000_000_000, -123_456, -1, 0, 1, 77, 999_999_999];
+        let mut inserted = Vec::new();
+        for (k, line) in lines().into_iter().enumerate() {
+            if k % 3 == 0 {
+                t.insert_segment(line, -1000..1000);
+                inserted.push((line, -1000..1000));
+            } else {
+                t.insert(line);
+                inserted.push((line, t.domain()));
+            }
+            for &x in queries.iter() {
+                let expected = inserted
+                    .iter()
+                    .filter(|(_, range)| range.contains(&x))
+                    .map(|(l, _)| l.eval(x))
+                    .min()
+                    .unwrap_or(i64::MAX);
+                assert_eq!(t.query(x), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn it_only_allocates_vertices_overlapping_segments() {
+        let mut t = SparseLiChaoTree::<MinSpec<i64>>::new(0..1 << 20);
+        t.insert_segment(Line::new(1, 0), 0..1);
+        // One vertex per level on the path to the leaf '[0,1)'.
+        assert_eq!(t.nodes.len(), 21);
+        assert_eq!(t.query(0), 0);
+        assert_eq!(t.query(1), i64::MAX);
+    }
+}
@@ -0,0 +1,187 @@
+//! A segment tree storing the sorted values of each vertex, answering order
+//! statistic queries over ranges.
+
+use super::vertex_bounds;
+use std::iter::FromIterator;
+use std::ops::Range;
+
+/// A segment tree where each vertex stores the values it covers in sorted
+/// order. Uses 'O(n log n)' memory.
+pub struct MergeSortTree<T: Ord + Clone> {
+    /// The sorted values covered by each respective vertex in the tree.
+    sorted: Vec<Vec<T>>,
+    /// For each vertex in the tree their bound '[l,r)' represents the range it
+    /// covers.
+    bounds: Vec<(usize, usize)>,
+}
+
+impl<T: Ord + Clone> MergeSortTree<T> {
+    /// Create a merge sort tree over the values in 'xs'.
+    ///
+    /// Time complexity: O(n log n)
+    pub fn from_slice(xs: &[T]) -> Self {
+        let bounds = vertex_bounds(xs.len());
+        let mut sorted = vec![Vec::new(); bounds.len()];
+        for idx in (0..bounds.len()).rev() {
+            let (l, r) = bounds[idx];
+            if r - l == 1 {
+                sorted[idx] = vec![xs[l].clone()];
+            } else if r - l > 1 {
+                sorted[idx] = merge(&sorted[idx * 2 + 1], &sorted[idx * 2 + 2]);
+            }
+        }
+        MergeSortTree { sorted, bounds }
+    }
+
+    /// The number of values the tree is built over.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.bounds[0].1
+    }
+
+    /// True if the tree is built over zero values, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Panics if the range '[l,r)' is out of bounds.
+    fn check_range(&self, range: &Range<usize>) {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range {}..{} out of bounds",
+            range.start,
+            range.end
+        );
+    }
+
+    /// Count the values in '[l,r)' for which 'f' holds. The predicate must be
+    /// monotone over sorted values, holding for a prefix of them.
+    fn count_rec<F: Fn(&T) -> bool>(&self, idx: usize, l: usize, r: usize, f: &F) -> usize {
+        let (vl, vr) = self.bounds[idx];
+        if r <= vl || vr <= l {
+            0
+        } else if l <= vl && vr <= r {
+            self.sorted[idx].partition_point(f)
+        } else {
+            self.count_rec(idx * 2 + 1, l, r, f) + self.count_rec(idx * 2 + 2, l, r, f)
+        }
+    }
+
+    /// Count the values in '[l,r)' which are strictly less than 'x'.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log^2 n)
+    pub fn count_less(&self, range: Range<usize>, x: &T) -> usize {
+        self.check_range(&range);
+        self.count_rec(0, range.start, range.end, &|y| y < x)
+    }
+
+    /// Count the values in '[l,r)' which are less than or equal to 'x'.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log^2 n)
+    pub fn count_less_equal(&self, range: Range<usize>, x: &T) -> usize {
+        self.check_range(&range);
+        self.count_rec(0, range.start, range.end, &|y| y <= x)
+    }
+
+    /// Count the values in '[l,r)' which are equal to 'x'.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log^2 n)
+    pub fn frequency(&self, range: Range<usize>, x: &T) -> usize {
+        self.count_less_equal(range.clone(), x) - self.count_less(range, x)
+    }
+
+    /// The 'k'th smallest value in '[l,r)', counting from zero. Returns 'None'
+    /// if the range contains 'k' or fewer values.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log^3 n)
+    pub fn kth_smallest(&self, range: Range<usize>, k: usize) -> Option<T> {
+        self.check_range(&range);
+        if k >= range.end - range.start {
+            return None;
+        }
+        // Binary search over all values for the smallest one with more than
+        // 'k' values in the range less than or equal to it.
+        let all = &self.sorted[0];
+        let (mut lo, mut hi) = (0, all.len() - 1);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.count_less_equal(range.clone(), &all[mid]) > k {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        Some(all[lo].clone())
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<T> for MergeSortTree<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let xs: Vec<T> = iter.into_iter().collect();
+        MergeSortTree::from_slice(&xs)
+    }
+}
+
+/// Merge the two sorted slices 'a' and 'b'.
+fn merge<T: Ord + Clone>(a: &[T], b: &[T]) -> Vec<T> {
+    let mut merged = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if b[j] < a[i] {
+            merged.push(b[j].clone());
+            j += 1;
+        } else {
+            merged.push(a[i].clone());
+            i += 1;
+        }
+    }
+    merged.extend_from_slice(&a[i..]);
+    merged.extend_from_slice(&b[j..]);
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MergeSortTree;
+
+    #[test]
+    fn it_answers_order_statistics() {
+        let xs = [5, 1, 4, 4, 9, 2, 7, 4, 0, 3, 8];
+        let t: MergeSortTree<i32> = xs.iter().cloned().collect();
+        for l in 0..=xs.len() {
+            for r in l..=xs.len() {
+                let mut sorted = xs[l..r].to_vec();
+                sorted.sort_unstable();
+                for k in 0..=sorted.len() {
+                    assert_eq!(t.kth_smallest(l..r, k), sorted.get(k).cloned());
+                }
+                for x in -1..11 {
+                    let lt = sorted.iter().filter(|&&y| y < x).count();
+                    let eq = sorted.iter().filter(|&&y| y == x).count();
+                    assert_eq!(t.count_less(l..r, &x), lt);
+                    assert_eq!(t.count_less_equal(l..r, &x), lt + eq);
+                    assert_eq!(t.frequency(l..r, &x), eq);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_handles_empty_tree() {
+        let t = MergeSortTree::<u8>::from_slice(&[]);
+        assert!(t.is_empty());
+        assert_eq!(t.kth_smallest(0..0, 0), None);
+        assert_eq!(t.count_less(0..0, &3), 0);
+    }
+}
@@ -0,0 +1,311 @@
+//! A segment tree where updates create new versions of the tree while every
+//! old version remains available for queries.
+
+use super::{InvertibleSpec, SegmentTreeSpec};
+use std::ops::Range;
+
+/// A handle to one version of a persistent segment tree.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Version(usize);
+
+/// A vertex of the tree. Vertices are shared between versions and never
+/// modified after they have been created.
+struct Node<T> {
+    value: T,
+    /// Children of the vertex or 'None' if the vertex is a leaf.
+    children: Option<(usize, usize)>,
+}
+
+/// A segment tree which supports point updates by path copying. Each update
+/// creates a new version of the tree in O(log n) additional memory.
+pub struct PersistentSegmentTree<Spec: SegmentTreeSpec> {
+    /// The specification defining the default value and how to combine values.
+    spec: Spec,
+    /// The number of values the tree is built over.
+    n: usize,
+    /// Vertices of all versions of the tree.
+    nodes: Vec<Node<Spec::T>>,
+    /// The root vertex of each version.
+    roots: Vec<usize>,
+}
+
+impl<Spec: SegmentTreeSpec + Default> PersistentSegmentTree<Spec> {
+    /// Create a persistent segment tree over 'n' values, each set to
+    /// 'Spec::default()'.
+    ///
+    /// Time complexity: O(n)
+    pub fn new(n: usize) -> Self {
+        PersistentSegmentTree::with_spec(Default::default(), n)
+    }
+
+    /// Create a persistent segment tree over the values in 'xs'.
+    ///
+    /// Time complexity: O(n)
+    pub fn from_slice(xs: &[Spec::T]) -> Self {
+        PersistentSegmentTree::from_slice_with_spec(Default::default(), xs)
+    }
+}
+
+impl<Spec: SegmentTreeSpec> PersistentSegmentTree<Spec> {
+    /// Create a persistent segment tree over 'n' values using specification
+    /// 'spec'. Each value is set to 'spec.default()'.
+    ///
+    /// Time complexity: O(n)
+    pub fn with_spec(spec: Spec, n: usize) -> Self {
+        let xs = vec![spec.default(); n];
+        PersistentSegmentTree::from_slice_with_spec(spec, &xs)
+    }
+
+    /// Create a persistent segment tree over the values in 'xs' using
+    /// specification 'spec'.
+    ///
+    /// Time complexity: O(n)
+    pub fn from_slice_with_spec(spec: Spec, xs: &[Spec::T]) -> Self {
+        let mut t = PersistentSegmentTree {
+            spec,
+            n: xs.len(),
+            nodes: Vec::new(),
+            roots: Vec::new(),
+        };
+        let root = if xs.is_empty() {
+            let value = t.spec.default();
+            t.push_node(value, None)
+        } else {
+            t.build(xs, 0, xs.len())
+        };
+        t.roots.push(root);
+        t
+    }
+
+    /// Build the vertices covering '[l,r)' and return the root.
+    fn build(&mut self, xs: &[Spec::T], l: usize, r: usize) -> usize {
+        if r - l == 1 {
+            self.push_node(xs[l].clone(), None)
+        } else {
+            let m = l + (r - l) / 2;
+            let left = self.build(xs, l, m);
+            let right = self.build(xs, m, r);
+            self.push_children(left, right)
+        }
+    }
+
+    /// Add a vertex and return its index.
+    fn push_node(&mut self, value: Spec::T, children: Option<(usize, usize)>) -> usize {
+        self.nodes.push(Node { value, children });
+        self.nodes.len() - 1
+    }
+
+    /// Add an internal vertex with children 'left' and 'right' and return its
+    /// index.
+    fn push_children(&mut self, left: usize, right: usize) -> usize {
+        let value = self.spec.combine(
+            self.nodes[left].value.clone(),
+            self.nodes[right].value.clone(),
+        );
+        self.push_node(value, Some((left, right)))
+    }
+
+    /// The number of values the segment tree is built over.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    /// True if the segment tree is built over zero values, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// The version the tree was created with.
+    pub fn initial(&self) -> Version {
+        Version(0)
+    }
+
+    /// The most recently created version.
+    pub fn latest(&self) -> Version {
+        Version(self.roots.len() - 1)
+    }
+
+    /// The number of versions, including the initial one.
+    pub fn version_count(&self) -> usize {
+        self.roots.len()
+    }
+
+    /// Create a new version from 'version' with the value at index 'i' set to
+    /// 'x'. The old version is left unchanged.
+    ///
+    /// Panics if 'i' is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn update(&mut self, version: Version, i: usize, x: Spec::T) -> Version {
+        assert!(i < self.n, "index {} out of bounds", i);
+        let root = self.update_rec(self.roots[version.0], 0, self.n, i, x);
+        self.roots.push(root);
+        self.latest()
+    }
+
+    /// Copy the path from vertex 'idx' covering '[l,r)' down to index 'i' and
+    /// return the new vertex.
+    fn update_rec(&mut self, idx: usize, l: usize, r: usize, i: usize, x: Spec::T) -> usize {
+        match self.nodes[idx].children {
+            None => self.push_node(x, None),
+            Some((left, right)) => {
+                let m = l + (r - l) / 2;
+                if i < m {
+                    let left = self.update_rec(left, l, m, i, x);
+                    self.push_children(left, right)
+                } else {
+                    let right = self.update_rec(right, m, r, i, x);
+                    self.push_children(left, right)
+                }
+            }
+        }
+    }
+
+    /// Combine the values in the range '[l,r)' of 'version'. Returns
+    /// 'spec.default()' if the range is empty.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log n)
+    pub fn query(&self, version: Version, range: Range<usize>) -> Spec::T {
+        let Range { start, end } = range;
+        assert!(
+            start <= end && end <= self.n,
+            "range {}..{} out of bounds",
+            start,
+            end
+        );
+        self.query_rec(self.roots[version.0], 0, self.n, start, end)
+    }
+
+    /// Combine the values in '[l,r)' which are covered by vertex 'idx' with
+    /// bound '[vl,vr)'.
+    fn query_rec(&self, idx: usize, vl: usize, vr: usize, l: usize, r: usize) -> Spec::T {
+        if r <= vl || vr <= l || r <= l {
+            return self.spec.default();
+        }
+        let node = &self.nodes[idx];
+        match node.children {
+            Some((left, right)) if !(l <= vl && vr <= r) => {
+                let m = vl + (vr - vl) / 2;
+                self.spec.combine(
+                    self.query_rec(left, vl, m, l, r),
+                    self.query_rec(right, m, vr, l, r),
+                )
+            }
+            _ => node.value.clone(),
+        }
+    }
+}
+
+impl<Spec: InvertibleSpec> PersistentSegmentTree<Spec>
+where
+    Spec::T: PartialOrd,
+{
+    /// The smallest index 'i' such that the values in '[0,i]' of version 'hi',
+    /// with those of version 'lo' taken out, combine to more than 'k'. Returns
+    /// 'None' if there is no such index. Every value of 'hi' must be at least
+    /// the value of 'lo' at the same index.
+    ///
+    /// If version 'v' counts how many times each value occurs in the first 'v'
+    /// elements of a sequence, this is the 'k'th smallest value among elements
+    /// 'lo' to 'hi', counting from zero.
+    ///
+    /// Time complexity: O(log n)
+    pub fn kth_smallest(&self, lo: Version, hi: Version, k: Spec::T) -> Option<usize> {
+        let (mut a, mut b) = (self.roots[lo.0], self.roots[hi.0]);
+        if self.n == 0 || self.difference(b, a) <= k {
+            return None;
+        }
+        let mut k = k;
+        let (mut l, mut r) = (0, self.n);
+        // Both versions are built over the same bounds, so their vertices have
+        // the same shape.
+        while let (Some((al, ar)), Some((bl, br))) =
+            (self.nodes[a].children, self.nodes[b].children)
+        {
+            let m = l + (r - l) / 2;
+            let left = self.difference(bl, al);
+            if k < left {
+                a = al;
+                b = bl;
+                r = m;
+            } else {
+                k = self.spec.uncombine(k, left);
+                a = ar;
+                b = br;
+                l = m;
+            }
+        }
+        Some(l)
+    }
+
+    /// The value of vertex 'b' with the value of vertex 'a' taken out.
+    fn difference(&self, b: usize, a: usize) -> Spec::T {
+        self.spec
+            .uncombine(self.nodes[b].value.clone(), self.nodes[a].value.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PersistentSegmentTree;
+    use crate::segment_tree::{MinSpec, SumSpec};
+
+    #[test]
+    fn it_keeps_old_versions() {
+        let mut t = PersistentSegmentTree::<MinSpec<i32>>::from_slice(&[5, 3, 8, 6]);
+        let v0 = t.initial();
+        let v1 = t.update(v0, 1, 7);
+        let v2 = t.update(v1, 2, 1);
+        let v3 = t.update(v0, 3, 0);
+
+        assert_eq!(t.version_count(), 4);
+        assert_eq!(t.latest(), v3);
+        assert_eq!(t.query(v0, 0..4), 3);
+        assert_eq!(t.query(v1, 0..4), 5);
+        assert_eq!(t.query(v1, 1..3), 7);
+        assert_eq!(t.query(v2, 0..4), 1);
+        assert_eq!(t.query(v2, 0..2), 5);
+        assert_eq!(t.query(v3, 0..4), 0);
+        assert_eq!(t.query(v3, 1..3), 3);
+        assert_eq!(t.query(v3, 2..2), i32::MAX);
+    }
+
+    #[test]
+    fn it_finds_kth_smallest_in_range() {
+        // Version 'i + 1' counts the occurrences of each value in 'xs[0..=i]',
+        // so the counts in 'xs[l..r]' are the difference of versions 'r' and
+        // 'l'.
+        let xs = [4, 1, 3, 1, 0, 4, 2, 2];
+        let mut t = PersistentSegmentTree::<SumSpec<usize>>::new(5);
+        let mut versions = vec![t.initial()];
+        for &x in xs.iter() {
+            let v = *versions.last().unwrap();
+            let count = t.query(v, x..x + 1);
+            versions.push(t.update(v, x, count + 1));
+        }
+        for l in 0..xs.len() {
+            for r in l + 1..=xs.len() {
+                let mut sorted = xs[l..r].to_vec();
+                sorted.sort_unstable();
+                for (k, &x) in sorted.iter().enumerate() {
+                    assert_eq!(t.kth_smallest(versions[l], versions[r], k), Some(x));
+                }
+                assert_eq!(t.kth_smallest(versions[l], versions[r], r - l), None);
+            }
+        }
+    }
+
+    #[test]
+    fn it_handles_empty_tree() {
+        let t = PersistentSegmentTree::<SumSpec<i32>>::new(0);
+        assert!(t.is_empty());
+        assert_eq!(t.query(t.initial(), 0..0), 0);
+        assert_eq!(t.kth_smallest(t.initial(), t.initial(), 0), None);
+    }
+}
@@ -0,0 +1,208 @@
+//! A segment tree over a huge range of integer coordinates which only
+//! allocates the vertices that have been updated.
+
+use super::SegmentTreeSpec;
+use num::PrimInt;
+use std::cmp::{max, min};
+use std::ops::Range;
+
+/// A vertex of the tree covering some range of coordinates.
+struct Node<T> {
+    value: T,
+    /// Left and right child of the vertex, if they have been allocated.
+    children: [Option<usize>; 2],
+}
+
+/// A segment tree over the coordinates '[lo,hi)' of integer type 'K'. Every
+/// coordinate starts out as 'spec.default()' and vertices are only allocated
+/// when a coordinate below them is updated, so memory use is O(u log(hi - lo))
+/// after 'u' updates.
+pub struct SparseSegmentTree<Spec: SegmentTreeSpec, K: PrimInt = i64> {
+    /// The specification defining the default value and how to combine values.
+    spec: Spec,
+    /// The range of coordinates covered by the tree.
+    domain: Range<K>,
+    /// Allocated vertices. The root is always at index 0.
+    nodes: Vec<Node<Spec::T>>,
+}
+
+/// The midpoint of '[l,r)' rounded down, computed without overflowing.
+pub(super) fn midpoint<K: PrimInt>(l: K, r: K) -> K {
+    l + (r - l) / (K::one() + K::one())
+}
+
+impl<Spec: SegmentTreeSpec + Default, K: PrimInt> SparseSegmentTree<Spec, K> {
+    /// Create a sparse segment tree over the coordinates in 'domain'. The width
+    /// of the domain must be representable in 'K'.
+    ///
+    /// Time complexity: O(1)
+    pub fn new(domain: Range<K>) -> Self {
+        SparseSegmentTree::with_spec(Default::default(), domain)
+    }
+}
+
+impl<Spec: SegmentTreeSpec, K: PrimInt> SparseSegmentTree<Spec, K> {
+    /// Create a sparse segment tree over the coordinates in 'domain' using
+    /// specification 'spec'. The width of the domain must be representable in
+    /// 'K'.
+    ///
+    /// Time complexity: O(1)
+    pub fn with_spec(spec: Spec, domain: Range<K>) -> Self {
+        assert!(domain.start <= domain.end, "invalid domain");
+        let root = Node {
+            value: spec.default(),
+            children: [None, None],
+        };
+        SparseSegmentTree {
+            spec,
+            domain,
+            nodes: vec![root],
+        }
+    }
+
+    /// The range of coordinates covered by the tree.
+    pub fn domain(&self) -> Range<K> {
+        self.domain.clone()
+    }
+
+    /// The number of vertices which have been allocated.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// The child 'side' of vertex 'idx', allocating it if necessary.
+    fn child(&mut self, idx: usize, side: usize) -> usize {
+        match self.nodes[idx].children[side] {
+            Some(c) => c,
+            None => {
+                self.nodes.push(Node {
+                    value: self.spec.default(),
+                    children: [None, None],
+                });
+                let c = self.nodes.len() - 1;
+                self.nodes[idx].children[side] = Some(c);
+                c
+            }
+        }
+    }
+
+    /// The value of vertex 'idx' if allocated, otherwise 'spec.default()'.
+    fn value(&self, idx: Option<usize>) -> Spec::T {
+        match idx {
+            Some(c) => self.nodes[c].value.clone(),
+            None => self.spec.default(),
+        }
+    }
+
+    /// Set the value at coordinate 'i' to 'x'.
+    ///
+    /// Panics if 'i' is outside the domain.
+    ///
+    /// Time complexity: O(log(hi - lo))
+    pub fn update(&mut self, i: K, x: Spec::T) {
+        assert!(self.domain.contains(&i), "coordinate out of bounds");
+        let (mut l, mut r) = (self.domain.start, self.domain.end);
+        let mut path = Vec::new();
+        let mut idx = 0;
+        while r - l > K::one() {
+            path.push(idx);
+            let m = midpoint(l, r);
+            if i < m {
+                idx = self.child(idx, 0);
+                r = m;
+            } else {
+                idx = self.child(idx, 1);
+                l = m;
+            }
+        }
+        self.nodes[idx].value = x;
+        while let Some(idx) = path.pop() {
+            let [left, right] = self.nodes[idx].children;
+            self.nodes[idx].value = self.spec.combine(self.value(left), self.value(right));
+        }
+    }
+
+    /// Combine the values in the range '[l,r)'. Returns 'spec.default()' if
+    /// the range is empty.
+    ///
+    /// Panics if the range is not contained in the domain.
+    ///
+    /// Time complexity: O(log(hi - lo))
+    pub fn query(&self, range: Range<K>) -> Spec::T {
+        let Range { start, end } = range;
+        assert!(
+            self.domain.start <= start && start <= end && end <= self.domain.end,
+            "range out of bounds"
+        );
+        self.query_rec(Some(0), self.domain.start, self.domain.end, start, end)
+    }
+
+    /// Combine the values in '[l,r)' which are covered by vertex 'idx' with
+    /// bound '[vl,vr)'.
+    fn query_rec(&self, idx: Option<usize>, vl: K, vr: K, l: K, r: K) -> Spec::T {
+        let idx = match idx {
+            Some(idx) if max(vl, l) < min(vr, r) => idx,
+            _ => return self.spec.default(),
+        };
+        if l <= vl && vr <= r {
+            self.nodes[idx].value.clone()
+        } else {
+            let m = midpoint(vl, vr);
+            let [left, right] = self.nodes[idx].children;
+            self.spec.combine(
+                self.query_rec(left, vl, m, l, r),
+                self.query_rec(right, m, vr, l, r),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SparseSegmentTree;
+    use crate::segment_tree::{MaxSpec, SumSpec};
+
+    #[test]
+    fn it_answers_queries_over_huge_domain() {
+        let mut t = SparseSegmentTree::<SumSpec<i64>>::new(
+            -1_000_000_000_000_000_000..1_000_000_000_000_000_000,
+        );
+        t.update(-5, 3);
+        t.update(1_000_000_000_000, 4);
+        t.update(999_999_999_999_999_999, 5);
+        t.update(-5, 1);
+
+        assert_eq!(t.query(t.domain()), 10);
+        assert_eq!(t.query(-5..-4), 1);
+        assert_eq!(t.query(-4..1_000_000_000_000), 0);
+        assert_eq!(t.query(0..1_000_000_000_001), 4);
+        assert_eq!(t.query(7..7), 0);
+        assert!(t.node_count() < 4 * 64);
+    }
+
+    #[test]
+    fn it_matches_dense_array() {
+        let n = 50u64;
+        let mut t = SparseSegmentTree::<MaxSpec<i32>, u64>::new(0..n);
+        let mut xs = vec![i32::MIN; n as usize];
+        for (k, i) in (0..n).map(|k| (k, k * 17 % n)) {
+            let x = (k * 31 % 23) as i32 - 11;
+            t.update(i, x);
+            xs[i as usize] = x;
+            for l in 0..n {
+                let r = (l + k + 1).min(n);
+                let expected = *xs[l as usize..r as usize].iter().max().unwrap();
+                assert_eq!(t.query(l..r), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn it_covers_whole_unsigned_range() {
+        let mut t = SparseSegmentTree::<SumSpec<u32>, u64>::new(0..u64::MAX);
+        t.update(0, 1);
+        t.update(u64::MAX - 1, 2);
+        assert_eq!(t.query(0..u64::MAX), 3);
+        assert_eq!(t.query(1..u64::MAX), 2);
+    }
+}
@@ -0,0 +1,263 @@
+//! A segment tree over a grid supporting point updates and rectangle queries.
+
+use super::SegmentTreeSpec;
+use std::ops::Range;
+
+/// A segment tree of segment trees over an 'n' by 'm' grid. Both dimensions use
+/// the bottom-up layout of 'IterativeSegmentTree', so vertex '(i, j)' covers
+/// the rows covered by row vertex 'i' and the columns covered by column vertex
+/// 'j'.
+///
+/// Values in a rectangle are not combined in any particular order, so the
+/// combine function of the specification must be commutative.
+pub struct SegmentTree2D<Spec: SegmentTreeSpec> {
+    /// The specification defining the default value and how to combine values.
+    spec: Spec,
+    /// The number of rows in the grid.
+    n: usize,
+    /// The number of columns in the grid.
+    m: usize,
+    /// The values of each vertex, vertex '(i, j)' is stored at 'i * 2m + j'.
+    values: Vec<Spec::T>,
+}
+
+impl<Spec: SegmentTreeSpec + Default> SegmentTree2D<Spec> {
+    /// Create a segment tree over an 'n' by 'm' grid with every value set to
+    /// 'Spec::default()'.
+    ///
+    /// Time complexity: O(nm)
+    pub fn new(n: usize, m: usize) -> Self {
+        SegmentTree2D::with_spec(Default::default(), n, m)
+    }
+
+    /// Create a segment tree over a grid given as a slice of equally long rows.
+    ///
+    /// Time complexity: O(nm)
+    pub fn from_rows(rows: &[Vec<Spec::T>]) -> Self {
+        SegmentTree2D::from_rows_with_spec(Default::default(), rows)
+    }
+}
+
+impl<Spec: SegmentTreeSpec> SegmentTree2D<Spec> {
+    /// Create a segment tree over an 'n' by 'm' grid using specification
+    /// 'spec'. Each value is set to 'spec.default()'.
+    ///
+    /// Time complexity: O(nm)
+    pub fn with_spec(spec: Spec, n: usize, m: usize) -> Self {
+        SegmentTree2D {
+            values: vec![spec.default(); 4 * n * m],
+            spec,
+            n,
+            m,
+        }
+    }
+
+    /// Create a segment tree over a grid given as a slice of equally long rows
+    /// using specification 'spec'.
+    ///
+    /// Panics if the rows are not of equal length.
+    ///
+    /// Time complexity: O(nm)
+    pub fn from_rows_with_spec(spec: Spec, rows: &[Vec<Spec::T>]) -> Self {
+        let n = rows.len();
+        let m = rows.first().map_or(0, |row| row.len());
+        let mut t = SegmentTree2D::with_spec(spec, n, m);
+        for (i, row) in rows.iter().enumerate() {
+            assert_eq!(row.len(), m, "rows must be of equal length");
+            let idx = t.index(i + n, m);
+            t.values[idx..idx + m].clone_from_slice(row);
+        }
+        for i in (1..2 * n).rev() {
+            if i < n {
+                for j in 1..2 * m {
+                    t.pull_row(i, j);
+                }
+            } else {
+                for j in (1..m).rev() {
+                    t.pull_column(i, j);
+                }
+            }
+        }
+        t
+    }
+
+    /// The number of rows and columns of the grid.
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.n, self.m)
+    }
+
+    /// Position of vertex '(i, j)' in 'values'.
+    fn index(&self, i: usize, j: usize) -> usize {
+        i * 2 * self.m + j
+    }
+
+    /// Recompute vertex '(i, j)' from row vertices '2i' and '2i + 1'.
+    fn pull_row(&mut self, i: usize, j: usize) {
+        let a = self.values[self.index(2 * i, j)].clone();
+        let b = self.values[self.index(2 * i + 1, j)].clone();
+        let idx = self.index(i, j);
+        self.values[idx] = self.spec.combine(a, b);
+    }
+
+    /// Recompute vertex '(i, j)' from column vertices '2j' and '2j + 1'.
+    fn pull_column(&mut self, i: usize, j: usize) {
+        let a = self.values[self.index(i, 2 * j)].clone();
+        let b = self.values[self.index(i, 2 * j + 1)].clone();
+        let idx = self.index(i, j);
+        self.values[idx] = self.spec.combine(a, b);
+    }
+
+    /// Set the value at row 'i' and column 'j' to 'x'.
+    ///
+    /// Panics if '(i, j)' is out of bounds.
+    ///
+    /// Time complexity: O(log n log m)
+    pub fn update(&mut self, i: usize, j: usize, x: Spec::T) {
+        assert!(
+            i < self.n && j < self.m,
+            "index ({}, {}) out of bounds",
+            i,
+            j
+        );
+        let i = i + self.n;
+        let j = j + self.m;
+        let idx = self.index(i, j);
+        self.values[idx] = x;
+        let mut jj = j / 2;
+        while jj >= 1 {
+            self.pull_column(i, jj);
+            jj /= 2;
+        }
+        let mut ii = i / 2;
+        while ii >= 1 {
+            let mut jj = j;
+            while jj >= 1 {
+                self.pull_row(ii, jj);
+                jj /= 2;
+            }
+            ii /= 2;
+        }
+    }
+
+    /// Combine the values in rows '[r0,r1)' and columns '[c0,c1)'. Returns
+    /// 'spec.default()' if the rectangle is empty.
+    ///
+    /// Panics if the rectangle is out of bounds.
+    ///
+    /// Time complexity: O(log n log m)
+    pub fn query(&self, rows: Range<usize>, columns: Range<usize>) -> Spec::T {
+        assert!(
+            rows.start <= rows.end && rows.end <= self.n,
+            "rows {}..{} out of bounds",
+            rows.start,
+            rows.end
+        );
+        assert!(
+            columns.start <= columns.end && columns.end <= self.m,
+            "columns {}..{} out of bounds",
+            columns.start,
+            columns.end
+        );
+        let mut acc = self.spec.default();
+        let mut l = rows.start + self.n;
+        let mut r = rows.end + self.n;
+        while l < r {
+            if l % 2 == 1 {
+                acc = self.spec.combine(acc, self.query_row(l, &columns));
+                l += 1;
+            }
+            if r % 2 == 1 {
+                r -= 1;
+                acc = self.spec.combine(acc, self.query_row(r, &columns));
+            }
+            l /= 2;
+            r /= 2;
+        }
+        acc
+    }
+
+    /// Combine the values in columns '[c0,c1)' of row vertex 'i'.
+    fn query_row(&self, i: usize, columns: &Range<usize>) -> Spec::T {
+        let mut acc = self.spec.default();
+        let mut l = columns.start + self.m;
+        let mut r = columns.end + self.m;
+        while l < r {
+            if l % 2 == 1 {
+                acc = self
+                    .spec
+                    .combine(acc, self.values[self.index(i, l)].clone());
+                l += 1;
+            }
+            if r % 2 == 1 {
+                r -= 1;
+                acc = self
+                    .spec
+                    .combine(acc, self.values[self.index(i, r)].clone());
+            }
+            l /= 2;
+            r /= 2;
+        }
+        acc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SegmentTree2D;
+    use crate::segment_tree::{MinSpec, SumSpec};
+
+    /// A 5 by 4 grid of small values.
+    fn grid() -> Vec<Vec<i64>> {
+        (0..5)
+            .map(|i| (0..4).map(|j| (i * 7 + j * 3) % 10).collect())
+            .collect()
+    }
+
+    /// Check every rectangle query against folding the grid directly.
+    fn check_all_rectangles(t: &SegmentTree2D<SumSpec<i64>>, grid: &[Vec<i64>]) {
+        let (n, m) = t.dimensions();
+        for r0 in 0..=n {
+            for r1 in r0..=n {
+                for c0 in 0..=m {
+                    for c1 in c0..=m {
+                        let expected: i64 = grid[r0..r1]
+                            .iter()
+                            .map(|row| row[c0..c1].iter().sum::<i64>())
+                            .sum();
+                        assert_eq!(t.query(r0..r1, c0..c1), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_answers_rectangle_queries() {
+        let grid = grid();
+        let t = SegmentTree2D::<SumSpec<i64>>::from_rows(&grid);
+        check_all_rectangles(&t, &grid);
+    }
+
+    #[test]
+    fn it_updates_values() {
+        let mut grid = vec![vec![0; 4]; 5];
+        let mut t = SegmentTree2D::<SumSpec<i64>>::new(5, 4);
+        for (i, row) in self::grid().into_iter().enumerate() {
+            for (j, x) in row.into_iter().enumerate() {
+                t.update(i, j, x);
+                grid[i][j] = x;
+            }
+            check_all_rectangles(&t, &grid);
+        }
+    }
+
+    #[test]
+    fn it_answers_min_queries() {
+        let mut t = SegmentTree2D::<MinSpec<i64>>::from_rows(&grid());
+        assert_eq!(t.query(1..3, 1..3), 0);
+        assert_eq!(t.query(3..5, 0..2), 1);
+        t.update(4, 0, -3);
+        assert_eq!(t.query(3..5, 0..2), -3);
+        assert_eq!(t.query(0..0, 0..4), i64::MAX);
+    }
+}
@@ -0,0 +1,407 @@
+//! Wavelet matrix answering rank, select and order statistic queries over a
+//! static sequence.
+
+use std::iter::FromIterator;
+use std::ops::Range;
+
+/// A fixed sequence of bits supporting rank queries in constant time.
+struct BitVector {
+    /// The bits packed into words of 64 bits.
+    words: Vec<u64>,
+    /// 'ranks[w]' is the number of ones in the words before word 'w'.
+    ranks: Vec<usize>,
+}
+
+impl BitVector {
+    /// Create a bit vector from 'bits'.
+    fn new(bits: &[bool]) -> Self {
+        let mut words = vec![0u64; bits.len() / 64 + 1];
+        for (i, &bit) in bits.iter().enumerate() {
+            if bit {
+                words[i / 64] |= 1 << (i % 64);
+            }
+        }
+        let mut ranks = Vec::with_capacity(words.len());
+        let mut acc = 0;
+        for w in words.iter() {
+            ranks.push(acc);
+            acc += w.count_ones() as usize;
+        }
+        BitVector { words, ranks }
+    }
+
+    /// The bit at index 'i'.
+    fn get(&self, i: usize) -> bool {
+        self.words[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    /// The number of ones in '[0,i)'.
+    fn rank1(&self, i: usize) -> usize {
+        let mask = (1u64 << (i % 64)) - 1;
+        self.ranks[i / 64] + (self.words[i / 64] & mask).count_ones() as usize
+    }
+
+    /// The number of zeros in '[0,i)'.
+    fn rank0(&self, i: usize) -> usize {
+        i - self.rank1(i)
+    }
+
+    /// The index of the 'k'th bit equal to 'bit', counting from zero. Such a
+    /// bit must exist.
+    fn select(&self, bit: bool, k: usize) -> usize {
+        let rank = |i| if bit { self.rank1(i) } else { self.rank0(i) };
+        // Smallest 'i' such that '[0,i]' contains 'k + 1' matching bits.
+        let (mut lo, mut hi) = (0, self.words.len() * 64 - 1);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if rank(mid + 1) > k {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        lo
+    }
+}
+
+/// A wavelet matrix over a sequence of 'n' values with 'sigma' distinct values.
+/// Values are compressed to their rank among the distinct values, and level
+/// 'd' stably partitions the sequence by bit 'd' of the compressed values,
+/// starting with the most significant bit.
+pub struct WaveletMatrix<T: Ord + Clone> {
+    /// The sorted distinct values of the sequence.
+    values: Vec<T>,
+    /// The number of values in the sequence.
+    n: usize,
+    /// The bits of each level, most significant bit first.
+    levels: Vec<BitVector>,
+    /// The number of zeros in each respective level.
+    zeros: Vec<usize>,
+}
+
+impl<T: Ord + Clone> WaveletMatrix<T> {
+    /// Create a wavelet matrix over the values in 'xs'.
+    ///
+    /// Time complexity: O(n log n)
+    pub fn from_slice(xs: &[T]) -> Self {
+        let mut values = xs.to_vec();
+        values.sort_unstable();
+        values.dedup();
+        let mut cur: Vec<usize> = xs
+            .iter()
+            .map(|x| values.binary_search(x).unwrap())
+            .collect();
+        let height = (usize::BITS - values.len().saturating_sub(1).leading_zeros()) as usize;
+        let mut levels = Vec::with_capacity(height);
+        let mut zeros = Vec::with_capacity(height);
+        for d in (0..height).rev() {
+            let bits: Vec<bool> = cur.iter().map(|&c| c >> d & 1 == 1).collect();
+            levels.push(BitVector::new(&bits));
+            let (mut next, ones): (Vec<usize>, Vec<usize>) =
+                cur.iter().partition(|&&c| c >> d & 1 == 0);
+            zeros.push(next.len());
+            next.extend(ones);
+            cur = next;
+        }
+        WaveletMatrix {
+            values,
+            n: xs.len(),
+            levels,
+            zeros,
+        }
+    }
+
+    /// The number of values in the sequence.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    /// True if the sequence is empty, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Panics if the range '[l,r)' is out of bounds.
+    fn check_range(&self, range: &Range<usize>) {
+        assert!(
+            range.start <= range.end && range.end <= self.n,
+            "range {}..{} out of bounds",
+            range.start,
+            range.end
+        );
+    }
+
+    /// The bit of compressed value 'c' inspected by level 'level'.
+    fn bit(&self, c: usize, level: usize) -> bool {
+        c >> (self.levels.len() - 1 - level) & 1 == 1
+    }
+
+    /// Map the index 'i' of level 'level' to the next level, following 'bit'.
+    fn descend(&self, level: usize, i: usize, bit: bool) -> usize {
+        if bit {
+            self.zeros[level] + self.levels[level].rank1(i)
+        } else {
+            self.levels[level].rank0(i)
+        }
+    }
+
+    /// The value at index 'i'.
+    ///
+    /// Panics if 'i' is out of bounds.
+    ///
+    /// Time complexity: O(log sigma)
+    pub fn get(&self, i: usize) -> T {
+        assert!(i < self.n, "index {} out of bounds", i);
+        let mut i = i;
+        let mut c = 0;
+        for level in 0..self.levels.len() {
+            let bit = self.levels[level].get(i);
+            c = c << 1 | bit as usize;
+            i = self.descend(level, i, bit);
+        }
+        self.values[c].clone()
+    }
+
+    /// Count the occurrences of 'x' in '[l,r)'.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log sigma)
+    pub fn frequency(&self, range: Range<usize>, x: &T) -> usize {
+        self.check_range(&range);
+        let c = match self.values.binary_search(x) {
+            Ok(c) => c,
+            Err(_) => return 0,
+        };
+        let (mut l, mut r) = (range.start, range.end);
+        for level in 0..self.levels.len() {
+            let bit = self.bit(c, level);
+            l = self.descend(level, l, bit);
+            r = self.descend(level, r, bit);
+        }
+        r - l
+    }
+
+    /// Count the occurrences of 'x' in '[0,i)'.
+    ///
+    /// Panics if 'i' is out of bounds.
+    ///
+    /// Time complexity: O(log sigma)
+    pub fn rank(&self, x: &T, i: usize) -> usize {
+        self.frequency(0..i, x)
+    }
+
+    /// The index of the 'k'th occurrence of 'x', counting from zero. Returns
+    /// 'None' if 'x' occurs 'k' or fewer times.
+    ///
+    /// Time complexity: O(log sigma log n)
+    pub fn select(&self, x: &T, k: usize) -> Option<usize> {
+        if k >= self.frequency(0..self.n, x) {
+            return None;
+        }
+        let c = self.values.binary_search(x).unwrap();
+        // Find where the occurrences of 'x' start after the last level, then
+        // follow the 'k'th occurrence back up to the sequence.
+        let mut i = 0;
+        for level in 0..self.levels.len() {
+            i = self.descend(level, i, self.bit(c, level));
+        }
+        i += k;
+        for level in (0..self.levels.len()).rev() {
+            i = if self.bit(c, level) {
+                self.levels[level].select(true, i - self.zeros[level])
+            } else {
+                self.levels[level].select(false, i)
+            };
+        }
+        Some(i)
+    }
+
+    /// The 'k'th smallest value in '[l,r)', counting from zero. Returns 'None'
+    /// if the range contains 'k' or fewer values.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log sigma)
+    pub fn kth_smallest(&self, range: Range<usize>, k: usize) -> Option<T> {
+        self.check_range(&range);
+        if k >= range.end - range.start {
+            return None;
+        }
+        let (mut l, mut r, mut k) = (range.start, range.end, k);
+        let mut c = 0;
+        for level in 0..self.levels.len() {
+            let zeros = self.levels[level].rank0(r) - self.levels[level].rank0(l);
+            let bit = k >= zeros;
+            if bit {
+                k -= zeros;
+            }
+            c = c << 1 | bit as usize;
+            l = self.descend(level, l, bit);
+            r = self.descend(level, r, bit);
+        }
+        Some(self.values[c].clone())
+    }
+
+    /// The 'k'th largest value in '[l,r)', counting from zero. Returns 'None'
+    /// if the range contains 'k' or fewer values.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log sigma)
+    pub fn kth_largest(&self, range: Range<usize>, k: usize) -> Option<T> {
+        self.check_range(&range);
+        let len = range.end - range.start;
+        if k >= len {
+            return None;
+        }
+        self.kth_smallest(range, len - 1 - k)
+    }
+
+    /// Count the values in '[l,r)' whose compressed value is less than 'c'.
+    fn count_below(&self, range: Range<usize>, c: usize) -> usize {
+        self.check_range(&range);
+        if c >= self.values.len() {
+            return range.end - range.start;
+        }
+        let (mut l, mut r) = (range.start, range.end);
+        let mut count = 0;
+        for level in 0..self.levels.len() {
+            let bit = self.bit(c, level);
+            if bit {
+                count += self.levels[level].rank0(r) - self.levels[level].rank0(l);
+            }
+            l = self.descend(level, l, bit);
+            r = self.descend(level, r, bit);
+        }
+        count
+    }
+
+    /// Count the values in '[l,r)' which are strictly less than 'x'.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log sigma)
+    pub fn count_less(&self, range: Range<usize>, x: &T) -> usize {
+        self.count_below(range, self.values.partition_point(|y| y < x))
+    }
+
+    /// Count the values in '[l,r)' which are less than or equal to 'x'.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log sigma)
+    pub fn count_less_equal(&self, range: Range<usize>, x: &T) -> usize {
+        self.count_below(range, self.values.partition_point(|y| y <= x))
+    }
+
+    /// Count the values in '[l,r)' which lie within 'values'.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(log sigma)
+    pub fn count_between(&self, range: Range<usize>, values: Range<T>) -> usize {
+        if values.end <= values.start {
+            self.check_range(&range);
+            return 0;
+        }
+        self.count_less(range.clone(), &values.end) - self.count_less(range, &values.start)
+    }
+}
+
+impl<T: Ord + Clone> FromIterator<T> for WaveletMatrix<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let xs: Vec<T> = iter.into_iter().collect();
+        WaveletMatrix::from_slice(&xs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BitVector, WaveletMatrix};
+
+    #[test]
+    fn bit_vector_ranks_and_selects() {
+        let bits: Vec<bool> = (0..200).map(|i| i % 3 == 0 || i % 7 == 0).collect();
+        let bv = BitVector::new(&bits);
+        let mut ones = 0;
+        for (i, &bit) in bits.iter().enumerate() {
+            assert_eq!(bv.get(i), bit);
+            assert_eq!(bv.rank1(i), ones);
+            if bit {
+                assert_eq!(bv.select(true, ones), i);
+                ones += 1;
+            } else {
+                assert_eq!(bv.select(false, i - ones), i);
+            }
+        }
+        assert_eq!(bv.rank1(bits.len()), ones);
+    }
+
+    #[test]
+    fn it_answers_order_statistics() {
+        let xs: Vec<i64> = (0..60).map(|i| (i * 37 + 11) % 23 - 8).collect();
+        let t: WaveletMatrix<i64> = xs.iter().cloned().collect();
+        assert_eq!(t.len(), xs.len());
+        for (i, &x) in xs.iter().enumerate() {
+            assert_eq!(t.get(i), x);
+        }
+        for l in 0..=xs.len() {
+            for r in l..=xs.len() {
+                let mut sorted = xs[l..r].to_vec();
+                sorted.sort_unstable();
+                for k in 0..=sorted.len() {
+                    assert_eq!(t.kth_smallest(l..r, k), sorted.get(k).cloned());
+                    let largest = sorted.iter().rev().nth(k).cloned();
+                    assert_eq!(t.kth_largest(l..r, k), largest);
+                }
+                for x in -10..16 {
+                    let lt = sorted.iter().filter(|&&y| y < x).count();
+                    let eq = sorted.iter().filter(|&&y| y == x).count();
+                    assert_eq!(t.count_less(l..r, &x), lt);
+                    assert_eq!(t.count_less_equal(l..r, &x), lt + eq);
+                    assert_eq!(t.frequency(l..r, &x), eq);
+                    let between = sorted.iter().filter(|&&y| x <= y && y < x + 5).count();
+                    assert_eq!(t.count_between(l..r, x..x + 5), between);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_ranks_and_selects() {
+        let xs = ["b", "a", "c", "a", "b", "a"];
+        let t = WaveletMatrix::from_slice(&xs);
+        assert_eq!(t.rank(&"a", 4), 2);
+        assert_eq!(t.rank(&"b", 6), 2);
+        assert_eq!(t.rank(&"d", 6), 0);
+        assert_eq!(t.select(&"a", 0), Some(1));
+        assert_eq!(t.select(&"a", 2), Some(5));
+        assert_eq!(t.select(&"a", 3), None);
+        assert_eq!(t.select(&"c", 0), Some(2));
+        assert_eq!(t.select(&"d", 0), None);
+    }
+
+    #[test]
+    fn it_handles_single_distinct_value() {
+        let t = WaveletMatrix::from_slice(&[7, 7, 7]);
+        assert_eq!(t.get(1), 7);
+        assert_eq!(t.kth_smallest(0..3, 2), Some(7));
+        assert_eq!(t.select(&7, 2), Some(2));
+        assert_eq!(t.count_less(0..3, &7), 0);
+        assert_eq!(t.count_less_equal(0..3, &7), 3);
+    }
+
+    #[test]
+    fn it_handles_empty_sequence() {
+        let t = WaveletMatrix::<u32>::from_slice(&[]);
+        assert!(t.is_empty());
+        assert_eq!(t.kth_smallest(0..0, 0), None);
+        assert_eq!(t.frequency(0..0, &1), 0);
+        assert_eq!(t.select(&1, 0), None);
+    }
+}
@@ -0,0 +1,163 @@
+//! Static range queries in constant time for idempotent operations.
+
+use crate::segment_tree::SegmentTreeSpec;
+use crate::segment_tree::{AndSpec, ArgMaxSpec, ArgMinSpec, GcdSpec, MaxSpec, MinSpec, OrSpec};
+use num::{Bounded, Integer, PrimInt};
+use std::iter::FromIterator;
+use std::ops::Range;
+
+/// A segment tree specification where combining a value with itself has no
+/// effect, 'combine(a, a) == a'. Such a range can be covered by two
+/// overlapping ranges without counting any value twice.
+pub trait IdempotentSpec: SegmentTreeSpec {}
+
+impl<T: Bounded + Ord + Clone> IdempotentSpec for MinSpec<T> {}
+impl<T: Bounded + Ord + Clone> IdempotentSpec for MaxSpec<T> {}
+impl<T: Bounded + Ord + Clone> IdempotentSpec for ArgMinSpec<T> {}
+impl<T: Bounded + Ord + Clone> IdempotentSpec for ArgMaxSpec<T> {}
+impl<T: Integer + Clone> IdempotentSpec for GcdSpec<T> {}
+impl<T: PrimInt> IdempotentSpec for OrSpec<T> {}
+impl<T: PrimInt> IdempotentSpec for AndSpec<T> {}
+
+/// Base 2 logarithm of 'n' rounded down. Requires 'n > 0'.
+fn log2(n: usize) -> usize {
+    (usize::BITS - 1 - n.leading_zeros()) as usize
+}
+
+/// A data-structure which answers range queries over a fixed sequence of
+/// values in constant time.
+pub struct SparseTable<Spec: IdempotentSpec> {
+    /// The specification defining the default value and how to combine values.
+    spec: Spec,
+    /// 'table[k][i]' combines the values in '[i, i + 2^k)'.
+    table: Vec<Vec<Spec::T>>,
+}
+
+impl<Spec: IdempotentSpec + Default> SparseTable<Spec> {
+    /// Create a sparse table over the values in 'xs'.
+    ///
+    /// Time complexity: O(n log n)
+    pub fn from_slice(xs: &[Spec::T]) -> Self {
+        SparseTable::from_slice_with_spec(Default::default(), xs)
+    }
+}
+
+impl<Spec: IdempotentSpec> SparseTable<Spec> {
+    /// Create a sparse table over the values in 'xs' using specification
+    /// 'spec'.
+    ///
+    /// Time complexity: O(n log n)
+    pub fn from_slice_with_spec(spec: Spec, xs: &[Spec::T]) -> Self {
+        let mut table = vec![xs.to_vec()];
+        let mut k = 1;
+        while 1 << k <= xs.len() {
+            let prev = &table[k - 1];
+            let half = 1 << (k - 1);
+            let level = (0..=xs.len() - (1 << k))
+                .map(|i| spec.combine(prev[i].clone(), prev[i + half].clone()))
+                .collect();
+            table.push(level);
+            k += 1;
+        }
+        SparseTable { spec, table }
+    }
+
+    /// The specification the sparse table was created with.
+    pub fn spec(&self) -> &Spec {
+        &self.spec
+    }
+
+    /// The number of values the sparse table is built over.
+    ///
+    /// Time complexity: O(1)
+    pub fn len(&self) -> usize {
+        self.table[0].len()
+    }
+
+    /// True if the sparse table is built over zero values, false otherwise.
+    ///
+    /// Time complexity: O(1)
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Combine the values in the range '[l,r)'. Returns 'spec.default()' if
+    /// the range is empty.
+    ///
+    /// Panics if the range is out of bounds.
+    ///
+    /// Time complexity: O(1)
+    pub fn query(&self, range: Range<usize>) -> Spec::T {
+        let Range { start, end } = range;
+        assert!(
+            start <= end && end <= self.len(),
+            "range {}..{} out of bounds",
+            start,
+            end
+        );
+        if start == end {
+            return self.spec.default();
+        }
+        let k = log2(end - start);
+        self.spec.combine(
+            self.table[k][start].clone(),
+            self.table[k][end - (1 << k)].clone(),
+        )
+    }
+}
+
+impl<Spec: IdempotentSpec + Default> FromIterator<Spec::T> for SparseTable<Spec> {
+    fn from_iter<I: IntoIterator<Item = Spec::T>>(iter: I) -> Self {
+        let xs: Vec<Spec::T> = iter.into_iter().collect();
+        SparseTable::from_slice(&xs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{log2, SparseTable};
+    use crate::segment_tree::{ArgMinSpec, GcdSpec, MaxSpec, MinSpec};
+
+    #[test]
+    fn log2_rounds_down() {
+        assert_eq!(log2(1), 0);
+        for i in 1..16 {
+            assert_eq!(log2(1 << i), i);
+            assert_eq!(log2((1 << i) + 1), i);
+            assert_eq!(log2((1 << (i + 1)) - 1), i);
+        }
+    }
+
+    #[test]
+    fn it_answers_range_queries() {
+        let xs = [4, 2, 7, 1, 9, 3, 3, 8, 0, 6, 5];
+        let min_t = SparseTable::<MinSpec<i32>>::from_slice(&xs);
+        let max_t: SparseTable<MaxSpec<i32>> = xs.iter().cloned().collect();
+        for l in 0..xs.len() {
+            for r in l + 1..=xs.len() {
+                assert_eq!(min_t.query(l..r), *xs[l..r].iter().min().unwrap());
+                assert_eq!(max_t.query(l..r), *xs[l..r].iter().max().unwrap());
+            }
+        }
+        assert_eq!(min_t.query(4..4), i32::MAX);
+    }
+
+    #[test]
+    fn it_supports_other_idempotent_specs() {
+        let xs = [12u64, 18, 30, 45, 9];
+        let t = SparseTable::<GcdSpec<u64>>::from_slice(&xs);
+        assert_eq!(t.query(0..3), 6);
+        assert_eq!(t.query(2..5), 3);
+
+        let t: SparseTable<ArgMinSpec<u64>> = xs.iter().cloned().enumerate().collect();
+        assert_eq!(t.query(0..5), (4, 9));
+        assert_eq!(t.query(1..4), (1, 18));
+    }
+
+    #[test]
+    fn it_handles_empty_table() {
+        let t = SparseTable::<MinSpec<i32>>::from_slice(&[]);
+        assert!(t.is_empty());
+        assert_eq!(t.query(0..0), i32::MAX);
+    }
+}
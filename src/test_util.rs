@@ -0,0 +1,26 @@
+//! Helpers shared by the tests of several modules and the benchmarks.
+
+/// A small deterministic pseudo-random number generator for tests and
+/// benchmarks.
+pub struct Lcg(u64);
+
+impl Lcg {
+    /// Create a generator starting from 'seed'.
+    pub fn new(seed: u64) -> Self {
+        Lcg(seed)
+    }
+
+    /// The next pseudo-random value in '[0,2^31)'.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+
+    /// The next pseudo-random value in '[0,bound)'.
+    pub fn next(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}